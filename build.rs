@@ -11,19 +11,32 @@
 //! The build script also sets the linker flags to tell it which link script to use.
 
 use const_gen::*;
+use crc::{Crc, CRC_16_IBM_3740};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 use xz2::read::XzEncoder;
 
+#[allow(dead_code)]
+#[path = "src/bitmap.rs"]
+mod bitmap;
+use bitmap::Layout;
+
 fn main() {
     // Generate vial config at the root of project
     println!("cargo:rerun-if-changed=vial.json");
-    // println!("cargo:rerun-if-changed=keyboard.toml");
 
     generate_vial_config();
 
+    let keyboard_toml = read_keyboard_toml();
+    generate_sensor_config(&keyboard_toml);
+    generate_layer_names(&keyboard_toml);
+    generate_keymap_legends(&keyboard_toml);
+    check_user_keys(&keyboard_toml);
+    let display_size = generate_display_config(&keyboard_toml);
+    generate_sprites(display_size);
+
     // Put `memory.x` in our output directory and ensure it's
     // on the linker search path.
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
@@ -39,6 +52,11 @@ fn main() {
     // `memory.x` is changed.
     println!("cargo:rerun-if-changed=memory.x");
 
+    // The host tests link with the host's defaults
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("none") {
+        return;
+    }
+
     // Specify linker arguments.
 
     // `--nmagic` is required if memory section addresses are not aligned to 0x10000,
@@ -84,3 +102,825 @@ fn generate_vial_config() {
     .join("\n");
     fs::write(out_file, const_declarations).unwrap();
 }
+
+fn read_keyboard_toml() -> toml::Table {
+    println!("cargo:rerun-if-env-changed=KEYBOARD_TOML_PATH");
+    let path = env::var("KEYBOARD_TOML_PATH").unwrap_or_else(|_| "keyboard.toml".to_owned());
+    println!("cargo:rerun-if-changed={}", path);
+    let content =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Cannot read {}: {}", path, e));
+    content
+        .parse()
+        .unwrap_or_else(|e| panic!("Cannot parse {}: {}", path, e))
+}
+
+/// Size of a PMW3360/PMW3389 SROM image
+const SROM_SIZE: usize = 4094;
+
+/// Supported sensors with their CPI range and step: (name, min, max, step, needs SROM)
+const SENSORS: [(&str, u16, u16, u16, bool); 2] = [
+    ("pmw3360", 100, 12000, 100, true),
+    ("pmw3389", 50, 16000, 50, true),
+];
+
+/// Sensors fitted to some boards that the firmware can't drive yet, with the reason
+const UNSUPPORTED_SENSORS: [(&str, &str); 1] = [(
+    "paw3395",
+    "rmk's Pmw33xx driver has no spec for it, it is set up by a register sequence instead of \
+     an SROM upload",
+)];
+
+fn generate_sensor_config(keyboard_toml: &toml::Table) {
+    // Generated sensor config file
+    let out_file = Path::new(&env::var_os("OUT_DIR").unwrap()).join("sensor_generated.rs");
+
+    let sensor_config = keyboard_toml.get("sensor");
+    let sensor = sensor_config
+        .and_then(|sensor| sensor.get("type"))
+        .and_then(|sensor| sensor.as_str())
+        .unwrap_or("pmw3360");
+    if let Some((_, reason)) = UNSUPPORTED_SENSORS.iter().find(|(name, _)| *name == sensor) {
+        panic!(
+            "Sensor {} isn't supported yet: {}. See Sensors in README.md",
+            sensor, reason
+        );
+    }
+    let (_, cpi_min, cpi_max, cpi_step, needs_srom) = SENSORS
+        .iter()
+        .find(|(name, ..)| *name == sensor)
+        .copied()
+        .unwrap_or_else(|| panic!("Unknown sensor type {:?} in keyboard.toml", sensor));
+
+    let names = SENSORS.map(|(name, ..)| format!("\"{}\"", name)).join(", ");
+    println!("cargo:rustc-check-cfg=cfg(sensor, values({}))", names);
+    println!("cargo:rustc-cfg=sensor=\"{}\"", sensor);
+
+    let srom = match (needs_srom, srom_path(sensor_config)) {
+        (true, Some(path)) => read_srom(&path),
+        (true, None) => panic!(
+            "Sensor {} needs an SROM image, set `[sensor] srom` in keyboard.toml or SROM_PATH",
+            sensor
+        ),
+        (false, Some(path)) => panic!(
+            "Sensor {} takes no SROM image, but {} is configured in `[sensor] srom` of \
+             keyboard.toml or SROM_PATH",
+            sensor, path
+        ),
+        (false, None) => Vec::new(),
+    };
+    let crc = Crc::<u16>::new(&CRC_16_IBM_3740).checksum(&srom);
+
+    let const_declarations = [
+        const_declaration!(pub SENSOR_SROM = srom),
+        const_declaration!(pub SENSOR_SROM_CRC = crc),
+        const_declaration!(pub SENSOR_CPI_MIN = cpi_min),
+        const_declaration!(pub SENSOR_CPI_MAX = cpi_max),
+        const_declaration!(pub SENSOR_CPI_STEP = cpi_step),
+    ]
+    .map(|s| "#[allow(clippy::redundant_static_lifetimes)]\n".to_owned() + s.as_str())
+    .join("\n");
+    fs::write(out_file, const_declarations).unwrap();
+}
+
+/// Width of a column of the display layout in characters of the normal font
+const LAYER_NAME_LEN: usize = 5;
+
+fn generate_layer_names(keyboard_toml: &toml::Table) {
+    // Generated layer name file
+    let out_file = Path::new(&env::var_os("OUT_DIR").unwrap()).join("layer_names_generated.rs");
+
+    let layer_names: Vec<String> = keyboard_toml
+        .get("layer")
+        .and_then(|layers| layers.as_array())
+        .map(|layers| layers.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(
+            |(i, layer)| match layer.get("name").and_then(|name| name.as_str()) {
+                Some(name) => abbreviate_layer_name(name),
+                None => format!("L{}", i),
+            },
+        )
+        .collect();
+
+    let const_declarations = [const_declaration!(pub LAYER_NAMES = layer_names)]
+        .map(|s| "#[allow(clippy::redundant_static_lifetimes)]\n".to_owned() + s.as_str())
+        .join("\n");
+    fs::write(out_file, const_declarations).unwrap();
+}
+
+/// Fits a layer name into `LAYER_NAME_LEN` characters: uppercase, only letters and digits,
+/// vowels after the first character dropped if still too long ("DVORAK" -> "DVRK"),
+/// then truncated
+fn abbreviate_layer_name(name: &str) -> String {
+    let name: Vec<char> = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let name = if name.len() > LAYER_NAME_LEN {
+        let mut abbreviated = name[..1].to_vec();
+        abbreviated.extend(name[1..].iter().filter(|c| !"AEIOU".contains(**c)));
+        abbreviated
+    } else {
+        name
+    };
+    name.iter().take(LAYER_NAME_LEN).collect()
+}
+
+/// Supported OLED sizes: (name in keyboard.toml, width, height)
+const DISPLAY_SIZES: [(&str, u32, u32); 3] =
+    [("128x32", 128, 32), ("128x64", 128, 64), ("64x48", 64, 48)];
+/// Supported OLED controllers, as `Controller` in src/oled.rs
+const DISPLAY_CONTROLLERS: [&str; 3] = ["ssd1306", "sh1106", "ssd1309"];
+/// Width of a column of the page layout, as `COLUMN_WIDTH` in src/displaylayout.rs
+const DISPLAY_COLUMN_WIDTH: u32 = 32;
+
+/// Generates the display config, returns the width and height of the content after rotation
+fn generate_display_config(keyboard_toml: &toml::Table) -> (u32, u32) {
+    // Generated display config file
+    let out_file = Path::new(&env::var_os("OUT_DIR").unwrap()).join("display_generated.rs");
+
+    let display_config = keyboard_toml.get("display");
+    let seconds = |key: &str, default: u32| -> u32 {
+        match display_config.and_then(|display| display.get(key)) {
+            Some(value) => value
+                .as_integer()
+                .and_then(|value| u32::try_from(value).ok())
+                .unwrap_or_else(|| panic!("[display] {} in keyboard.toml must be seconds", key)),
+            None => default,
+        }
+    };
+    let dim_timeout = seconds("dim_timeout", 30);
+    let off_timeout = seconds("off_timeout", 300);
+    let shift_interval = seconds("shift_interval", 60);
+
+    let size = display_config
+        .and_then(|display| display.get("size"))
+        .map(|size| {
+            size.as_str()
+                .unwrap_or_else(|| panic!("[display] size in keyboard.toml must be a string"))
+        })
+        .unwrap_or("128x32");
+    let (_, width, height) = DISPLAY_SIZES
+        .iter()
+        .find(|(name, ..)| *name == size)
+        .copied()
+        .unwrap_or_else(|| panic!("Unknown display size {:?} in keyboard.toml", size));
+    let controller = display_config
+        .and_then(|display| display.get("controller"))
+        .map(|controller| {
+            controller
+                .as_str()
+                .unwrap_or_else(|| panic!("[display] controller in keyboard.toml must be a string"))
+        })
+        .unwrap_or("ssd1306");
+    if !DISPLAY_CONTROLLERS.contains(&controller) {
+        panic!(
+            "Unknown display controller {:?} in keyboard.toml",
+            controller
+        );
+    }
+    let rotation = match display_config.and_then(|display| display.get("rotation")) {
+        Some(rotation) => rotation
+            .as_integer()
+            .filter(|rotation| [0, 90, 180, 270].contains(rotation))
+            .unwrap_or_else(|| {
+                panic!("[display] rotation in keyboard.toml must be 0, 90, 180 or 270")
+            }) as u16,
+        None => 90,
+    };
+
+    let names = DISPLAY_CONTROLLERS
+        .map(|name| format!("\"{}\"", name))
+        .join(", ");
+    println!(
+        "cargo:rustc-check-cfg=cfg(display_controller, values({}))",
+        names
+    );
+    println!("cargo:rustc-cfg=display_controller=\"{}\"", controller);
+
+    let const_declarations = [
+        const_declaration!(pub DISPLAY_DIM_TIMEOUT_SECS = dim_timeout),
+        const_declaration!(pub DISPLAY_OFF_TIMEOUT_SECS = off_timeout),
+        const_declaration!(pub DISPLAY_SHIFT_INTERVAL_SECS = shift_interval),
+        const_declaration!(pub DISPLAY_WIDTH = width),
+        const_declaration!(pub DISPLAY_HEIGHT = height),
+        const_declaration!(pub DISPLAY_ROTATION_DEGREES = rotation),
+    ]
+    .map(|s| "#[allow(clippy::redundant_static_lifetimes)]\n".to_owned() + s.as_str())
+    .join("\n");
+    fs::write(out_file, const_declarations).unwrap();
+
+    if rotation % 180 == 0 {
+        (width, height)
+    } else {
+        (height, width)
+    }
+}
+
+/// Directory of the sprite animations, one subdirectory per animation
+const SPRITES_DIR: &str = "assets/sprites";
+/// Duration of a PNG frame without a duration in its file name, or of a GIF frame without delay
+const SPRITE_FRAME_MS: u32 = 100;
+
+/// A frame of a sprite, one `bool` per pixel, row by row, `true` is lit
+struct SpriteFrame {
+    width: u32,
+    height: u32,
+    pixels: Vec<bool>,
+    duration_ms: u32,
+}
+
+/// Converts the images in `SPRITES_DIR` into `Animation`s. A subdirectory `cat_idle` becomes
+/// `pub static CAT_IDLE: Animation` with either the frames of its animated GIF, or its PNG
+/// files in name order. A PNG frame is shown for the milliseconds at the end of its name
+/// (`01_400ms.png`), `SPRITE_FRAME_MS` otherwise. Bright opaque pixels are lit.
+///
+/// Sprites must fit into a column of the page layout on the `display_size` content.
+fn generate_sprites(display_size: (u32, u32)) {
+    // Generated sprite file
+    let out_file = Path::new(&env::var_os("OUT_DIR").unwrap()).join("sprites_generated.rs");
+    println!("cargo:rerun-if-changed={}", SPRITES_DIR);
+    println!("cargo:rerun-if-changed=src/bitmap.rs");
+
+    let mut directories: Vec<PathBuf> = match fs::read_dir(SPRITES_DIR) {
+        Ok(entries) => entries
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    directories.sort();
+
+    let (display_width, display_height) = display_size;
+    // As the columns of `Flow` in src/displaylayout.rs
+    let columns = (display_width / DISPLAY_COLUMN_WIDTH).max(1);
+    let max_size = (display_width / columns, display_height);
+
+    let mut animations = Vec::new();
+    for directory in directories {
+        let name = directory.file_name().unwrap().to_string_lossy().to_string();
+        let mut files: Vec<PathBuf> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        let has_extension = |path: &PathBuf, extension: &str| {
+            path.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+        };
+        let gifs: Vec<&PathBuf> = files.iter().filter(|f| has_extension(f, "gif")).collect();
+        let pngs: Vec<&PathBuf> = files.iter().filter(|f| has_extension(f, "png")).collect();
+
+        let frames = match (gifs.as_slice(), pngs.is_empty()) {
+            ([gif], true) => read_gif_frames(gif),
+            ([], false) => pngs.iter().map(|png| read_png_frame(png)).collect(),
+            _ => panic!(
+                "Sprite {} needs either one animated GIF or PNG frames",
+                directory.display()
+            ),
+        };
+        validate_sprite(&directory, &frames, max_size);
+        animations.push(sprite_declaration(&name, &frames));
+    }
+
+    fs::write(out_file, animations.join("\n")).unwrap();
+}
+
+fn read_png_frame(path: &Path) -> SpriteFrame {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e));
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .unwrap_or_else(|e| panic!("Cannot decode {}: {}", path.display(), e));
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .unwrap_or_else(|e| panic!("Cannot decode {}: {}", path.display(), e));
+
+    let channels = info.color_type.samples();
+    let pixels = buffer[..info.buffer_size()]
+        .chunks(channels)
+        .map(|pixel| match info.color_type {
+            png::ColorType::Grayscale => is_lit(pixel[0], pixel[0], pixel[0], 0xff),
+            png::ColorType::GrayscaleAlpha => is_lit(pixel[0], pixel[0], pixel[0], pixel[1]),
+            png::ColorType::Rgb => is_lit(pixel[0], pixel[1], pixel[2], 0xff),
+            png::ColorType::Rgba => is_lit(pixel[0], pixel[1], pixel[2], pixel[3]),
+            // Expanded to RGB by the decoder
+            png::ColorType::Indexed => unreachable!(),
+        })
+        .collect();
+
+    // `01_400ms.png` is shown for 400 ms
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let duration_ms = stem
+        .rsplit_once('_')
+        .and_then(|(_, duration)| duration.strip_suffix("ms"))
+        .and_then(|duration| duration.parse().ok())
+        .unwrap_or(SPRITE_FRAME_MS);
+
+    SpriteFrame {
+        width: info.width,
+        height: info.height,
+        pixels,
+        duration_ms,
+    }
+}
+
+fn read_gif_frames(path: &Path) -> Vec<SpriteFrame> {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e));
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options
+        .read_info(file)
+        .unwrap_or_else(|e| panic!("Cannot decode {}: {}", path.display(), e));
+    let (width, height) = (decoder.width() as usize, decoder.height() as usize);
+
+    // GIF frames may only cover a part of the image, they are drawn over the previous ones
+    let mut canvas = vec![[0u8; 4]; width * height];
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder
+        .read_next_frame()
+        .unwrap_or_else(|e| panic!("Cannot decode {}: {}", path.display(), e))
+    {
+        let previous = canvas.clone();
+        let (left, top) = (frame.left as usize, frame.top as usize);
+        for (i, pixel) in frame.buffer.chunks(4).enumerate() {
+            let (x, y) = (
+                left + i % frame.width as usize,
+                top + i / frame.width as usize,
+            );
+            // Transparent pixels keep the previous frame
+            if x < width && y < height && pixel[3] != 0 {
+                canvas[y * width + x] = pixel.try_into().unwrap();
+            }
+        }
+
+        frames.push(SpriteFrame {
+            width: width as u32,
+            height: height as u32,
+            pixels: canvas
+                .iter()
+                .map(|[r, g, b, a]| is_lit(*r, *g, *b, *a))
+                .collect(),
+            // GIF delays are in hundredths of a second
+            duration_ms: match frame.delay {
+                0 => SPRITE_FRAME_MS,
+                delay => delay as u32 * 10,
+            },
+        });
+
+        match frame.dispose {
+            gif::DisposalMethod::Background => {
+                for y in top..(top + frame.height as usize).min(height) {
+                    for x in left..(left + frame.width as usize).min(width) {
+                        canvas[y * width + x] = [0; 4];
+                    }
+                }
+            }
+            gif::DisposalMethod::Previous => canvas = previous,
+            gif::DisposalMethod::Any | gif::DisposalMethod::Keep => {}
+        }
+    }
+    frames
+}
+
+/// Bright opaque pixels are lit on the OLED
+fn is_lit(r: u8, g: u8, b: u8, a: u8) -> bool {
+    let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+    a >= 0x80 && luma >= 0x80
+}
+
+fn validate_sprite(directory: &Path, frames: &[SpriteFrame], (max_width, max_height): (u32, u32)) {
+    let Some(first) = frames.first() else {
+        panic!("Sprite {} has no frames", directory.display());
+    };
+    if first.width > max_width || first.height > max_height {
+        panic!(
+            "Sprite {} is {}x{}, larger than a column of the display ({}x{})",
+            directory.display(),
+            first.width,
+            first.height,
+            max_width,
+            max_height
+        );
+    }
+    if let Some(frame) = frames
+        .iter()
+        .find(|frame| (frame.width, frame.height) != (first.width, first.height))
+    {
+        panic!(
+            "Frames of sprite {} differ in size, {}x{} and {}x{}",
+            directory.display(),
+            first.width,
+            first.height,
+            frame.width,
+            frame.height
+        );
+    }
+}
+
+/// Declaration of an `Animation` of frames in the row layout of `ImageRaw`
+fn sprite_declaration(name: &str, frames: &[SpriteFrame]) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        panic!("Sprite name {} must start with a letter", name);
+    }
+
+    let width = frames[0].width;
+    let frames: Vec<String> = frames
+        .iter()
+        .map(|frame| {
+            let (width, height) = (frame.width as usize, frame.height as usize);
+            let mut image = vec![0u8; Layout::RowsMsb.len(width, height)];
+            for (i, on) in frame.pixels.iter().enumerate() {
+                Layout::RowsMsb.set(&mut image, width, i % width, i / width, *on);
+            }
+            let bytes: Vec<String> = image.iter().map(|byte| format!("{:#04x}", byte)).collect();
+            format!(
+                "        Frame {{\n            image: &[{}],\n            duration_ms: {},\n        }},",
+                bytes.join(", "),
+                frame.duration_ms
+            )
+        })
+        .collect();
+
+    format!(
+        "pub static {}: Animation = Animation {{\n    width: {},\n    frames: &[\n{}\n    ],\n    tempo: Tempo::Fixed,\n}};\n",
+        identifier,
+        width,
+        frames.join("\n")
+    )
+}
+
+/// Width of a key legend on the keymap page of the OLED
+const KEY_LEGEND_LEN: usize = 2;
+
+/// Legends of keyboard.toml key names that can't be abbreviated by their first characters
+const KEY_LEGENDS: &[(&str, &str)] = &[
+    ("grave", "`"),
+    ("comma", ","),
+    ("dot", "."),
+    ("slash", "/"),
+    ("bsls", "\\"),
+    ("backslash", "\\"),
+    ("minus", "-"),
+    ("equal", "="),
+    ("quote", "'"),
+    ("semicolon", ";"),
+    ("lbrc", "["),
+    ("rbrc", "]"),
+    ("space", "SP"),
+    ("enter", "EN"),
+    ("backspace", "BS"),
+    ("tab", "TB"),
+    ("esc", "ES"),
+    ("escape", "ES"),
+    ("del", "DL"),
+    ("delete", "DL"),
+    ("caps", "CL"),
+    ("lshift", "SH"),
+    ("rshift", "SH"),
+    ("lctrl", "CT"),
+    ("rctrl", "CT"),
+    ("lalt", "AL"),
+    ("ralt", "AL"),
+    ("lgui", "GU"),
+    ("rgui", "GU"),
+    ("page_up", "PU"),
+    ("page_down", "PD"),
+    ("left", "<"),
+    ("right", ">"),
+    ("up", "^"),
+    ("down", "v"),
+    ("ms_btn1", "M1"),
+    ("ms_btn2", "M2"),
+    ("mute", "MU"),
+    ("volu", "V+"),
+    ("vold", "V-"),
+    ("num", "NL"),
+    ("psls", "/"),
+    ("past", "*"),
+    ("pmns", "-"),
+    ("ppls", "+"),
+    ("peql", "="),
+    ("pdot", "."),
+];
+
+/// Characters typed by `WM(<key>, LShift)`
+const SHIFTED_LEGENDS: &[(&str, &str)] = &[("[", "{"), ("]", "}"), ("9", "("), ("0", ")")];
+
+fn generate_keymap_legends(keyboard_toml: &toml::Table) {
+    // Generated keymap legend file
+    let out_file = Path::new(&env::var_os("OUT_DIR").unwrap()).join("keymap_legends_generated.rs");
+
+    let positions = matrix_positions(keyboard_toml);
+
+    // The display is on the peripheral, so only its half of the matrix is shown
+    let peripheral = keyboard_toml
+        .get("split")
+        .and_then(|split| split.get("peripheral"))
+        .and_then(|peripherals| peripherals.as_array())
+        .and_then(|peripherals| peripherals.first());
+    let matrix_value = |key: &str| {
+        peripheral
+            .and_then(|peripheral| peripheral.get(key))
+            .and_then(|value| value.as_integer())
+            .unwrap_or(0) as usize
+    };
+    let (rows, cols) = (matrix_value("rows"), matrix_value("cols"));
+    let (row_offset, col_offset) = (matrix_value("row_offset"), matrix_value("col_offset"));
+
+    let aliases = keyboard_toml
+        .get("aliases")
+        .and_then(|aliases| aliases.as_table());
+
+    let layer_keys: Vec<Vec<String>> = keyboard_toml
+        .get("layer")
+        .and_then(|layers| layers.as_array())
+        .map(|layers| layers.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(i, layer)| {
+            let keys = split_keys(
+                layer
+                    .get("keys")
+                    .and_then(|keys| keys.as_str())
+                    .unwrap_or(""),
+            );
+            if keys.len() != positions.len() {
+                panic!(
+                    "Layer {} in keyboard.toml has {} keys, the matrix_map {}",
+                    i,
+                    keys.len(),
+                    positions.len()
+                );
+            }
+            let mut legends = vec![String::new(); rows * cols];
+            for (key, &(row, col)) in keys.iter().zip(&positions) {
+                if (row_offset..row_offset + rows).contains(&row)
+                    && (col_offset..col_offset + cols).contains(&col)
+                {
+                    legends[(row - row_offset) * cols + col - col_offset] =
+                        key_legend(key, aliases)
+                            .chars()
+                            .take(KEY_LEGEND_LEN)
+                            .collect();
+                }
+            }
+            legends
+        })
+        .collect();
+
+    let const_declarations = [
+        const_declaration!(pub KEYMAP_ROWS = rows),
+        const_declaration!(pub KEYMAP_COLS = cols),
+        const_declaration!(pub LAYER_KEYS = layer_keys),
+    ]
+    .map(|s| "#[allow(clippy::redundant_static_lifetimes)]\n".to_owned() + s.as_str())
+    .join("\n");
+    fs::write(out_file, const_declarations).unwrap();
+}
+
+/// Matrix position of every key in the order of the `keys` of a layer
+fn matrix_positions(keyboard_toml: &toml::Table) -> Vec<(usize, usize)> {
+    let matrix_map = keyboard_toml
+        .get("layout")
+        .and_then(|layout| layout.get("matrix_map"))
+        .and_then(|matrix_map| matrix_map.as_str())
+        .unwrap_or_default();
+    matrix_map
+        .split(')')
+        .filter_map(|entry| {
+            let mut parts = entry.trim().trim_start_matches('(').split(',');
+            let row = parts.next()?.trim().parse().ok()?;
+            let col = parts.next()?.trim().parse().ok()?;
+            Some((row, col))
+        })
+        .collect()
+}
+
+/// The firmware boots with the keymap of src/keymap.rs, while the legends on the display come
+/// from keyboard.toml. Our own keys are the `User` actions, they have to be at the same
+/// positions in both.
+fn check_user_keys(keyboard_toml: &toml::Table) {
+    println!("cargo:rerun-if-changed=src/keymap.rs");
+    let keymap = fs::read_to_string("src/keymap.rs").expect("Cannot read src/keymap.rs");
+    // (layer, row, col, user action)
+    let mut keymap_users = Vec::new();
+    for (layer, rows) in keymap_layers(&keymap).iter().enumerate() {
+        for (row, keys) in rows.iter().enumerate() {
+            for (col, key) in keys.iter().enumerate() {
+                if let Some(user) = key.strip_prefix("USER").and_then(|n| n.parse::<u8>().ok()) {
+                    keymap_users.push((layer, row, col, user));
+                }
+            }
+        }
+    }
+
+    let positions = matrix_positions(keyboard_toml);
+    let mut toml_users = Vec::new();
+    let layers = keyboard_toml
+        .get("layer")
+        .and_then(|layers| layers.as_array())
+        .map(|layers| layers.as_slice())
+        .unwrap_or_default();
+    for (layer, keys) in layers.iter().enumerate() {
+        let keys = split_keys(
+            keys.get("keys")
+                .and_then(|keys| keys.as_str())
+                .unwrap_or(""),
+        );
+        for (key, &(row, col)) in keys.iter().zip(&positions) {
+            let user = key
+                .get(..4)
+                .filter(|user| user.eq_ignore_ascii_case("user"));
+            if let Some(user) = user.and_then(|_| key[4..].parse::<u8>().ok()) {
+                toml_users.push((layer, row, col, user));
+            }
+        }
+    }
+
+    keymap_users.sort();
+    toml_users.sort();
+    if keymap_users != toml_users {
+        let missing = |from: &[(usize, usize, usize, u8)], of: &[(usize, usize, usize, u8)]| {
+            from.iter()
+                .filter(|key| !of.contains(key))
+                .map(|(layer, row, col, user)| {
+                    format!("User{} at layer {} ({}, {})", user, layer, row, col)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        panic!(
+            "The User keys of src/keymap.rs and keyboard.toml differ. Only in src/keymap.rs: [{}], only in keyboard.toml: [{}]",
+            missing(&keymap_users, &toml_users),
+            missing(&toml_users, &keymap_users)
+        );
+    }
+}
+
+/// The keys of the `layer!` rows of src/keymap.rs as written, by layer and row
+fn keymap_layers(keymap: &str) -> Vec<Vec<Vec<String>>> {
+    let keymap: String = keymap
+        .lines()
+        .map(|line| line.split_once("//").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut layers = Vec::new();
+    for layer in keymap.split("layer!(").skip(1) {
+        let mut rows = Vec::new();
+        let mut row: Option<Vec<String>> = None;
+        let mut key = String::new();
+        // Brackets of the layer, of the rows, parentheses of the keys
+        let mut depth = 0;
+        for c in layer.chars() {
+            match c {
+                '[' | '(' => depth += 1,
+                ']' | ')' => depth -= 1,
+                _ => {}
+            }
+            match (depth, c) {
+                // End of the layer
+                (0, _) => break,
+                (2, '[') => row = Some(Vec::new()),
+                (1, ']') | (2, ',') => {
+                    let key = std::mem::take(&mut key);
+                    if let Some(keys) = row.as_mut().filter(|_| !key.trim().is_empty()) {
+                        keys.push(key.trim().to_owned());
+                    }
+                    if c == ']' {
+                        rows.extend(row.take());
+                    }
+                }
+                (2.., _) if row.is_some() => key.push(c),
+                _ => {}
+            }
+        }
+        layers.push(rows);
+    }
+    layers
+}
+
+/// Splits the `keys` of a layer at whitespace outside of parentheses
+fn split_keys(keys: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, c) in keys.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    split.push(&keys[start..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        split.push(&keys[start..]);
+    }
+    split
+}
+
+/// Short legend of a key of keyboard.toml, empty for transparent and unused keys
+fn key_legend(key: &str, aliases: Option<&toml::Table>) -> String {
+    if key.chars().all(|c| c == '_') || key.eq_ignore_ascii_case("no") {
+        return String::new();
+    }
+    if let Some(alias) = key.strip_prefix('@') {
+        return match aliases.and_then(|aliases| aliases.get(alias)?.as_str()) {
+            Some(key) => key_legend(key, aliases),
+            None => alias.to_uppercase(),
+        };
+    }
+    if let Some((function, args)) = key.strip_suffix(')').and_then(|key| key.split_once('(')) {
+        let mut args = args.split(',').map(str::trim);
+        let first = args.next().unwrap_or_default();
+        return match function {
+            // Layer keys show the layer, tap-hold keys their tap action
+            "MO" | "TG" | "TO" | "TT" | "OSL" | "DF" | "LT" => first.to_uppercase(),
+            "WM" if args.any(|modifier| modifier.ends_with("Shift")) => SHIFTED_LEGENDS
+                .iter()
+                .find(|(key, _)| *key == first)
+                .map(|(_, shifted)| shifted.to_string())
+                .unwrap_or_else(|| key_legend(first, aliases)),
+            _ => key_legend(first, aliases),
+        };
+    }
+
+    let lower = key.to_lowercase();
+    if let Some((_, legend)) = KEY_LEGENDS.iter().find(|(name, _)| *name == lower) {
+        return legend.to_string();
+    }
+    if let Some(number) = lower.strip_prefix("kp_") {
+        return number.to_owned();
+    }
+    if let Some(number) = lower.strip_prefix("macro") {
+        return format!("M{}", number);
+    }
+    if let Some(number) = lower.strip_prefix("user") {
+        return format!("U{}", number);
+    }
+    // F10 to F24 don't fit, show the number only
+    if let Some(number) = lower.strip_prefix('f').filter(|n| n.parse::<u8>().is_ok()) {
+        return if number.len() > 1 {
+            number.to_owned()
+        } else {
+            format!("F{}", number)
+        };
+    }
+    key.to_uppercase()
+}
+
+/// The configured SROM image, `SROM_PATH` overrides `[sensor] srom` of keyboard.toml
+fn srom_path(sensor_config: Option<&toml::Value>) -> Option<String> {
+    println!("cargo:rerun-if-env-changed=SROM_PATH");
+    env::var("SROM_PATH").ok().or_else(|| {
+        sensor_config
+            .and_then(|sensor| sensor.get("srom"))
+            .map(|srom| {
+                srom.as_str()
+                    .unwrap_or_else(|| panic!("[sensor] srom in keyboard.toml must be a path"))
+                    .to_owned()
+            })
+    })
+}
+
+fn read_srom(srom_path: &str) -> Vec<u8> {
+    println!("cargo:rerun-if-changed={}", srom_path);
+
+    let srom =
+        fs::read(srom_path).unwrap_or_else(|e| panic!("Cannot read SROM {}: {}", srom_path, e));
+    if srom.len() != SROM_SIZE {
+        panic!(
+            "SROM {} has {} bytes, expected {}",
+            srom_path,
+            srom.len(),
+            SROM_SIZE
+        );
+    }
+    // Every SROM image starts with 0x01 followed by the firmware version reported in SROM_ID
+    if srom[0] != 0x01 || srom[1] == 0x00 {
+        panic!(
+            "SROM {} has an invalid header {:#04x} {:#04x}",
+            srom_path, srom[0], srom[1]
+        );
+    }
+    srom
+}
@@ -0,0 +1,35 @@
+use embassy_time::{Duration, Instant};
+use rmk_macro::event;
+
+/// Shortest time between two activities passed on by the same source, the display only needs
+/// to know about activity once in a while to stay awake
+const ACTIVITY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The ball moved on the central, or a key was pressed on either half
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct ActivityEvent;
+
+/// Lets through one activity per [`ACTIVITY_INTERVAL`]
+#[derive(Default)]
+pub struct ActivityThrottle {
+    last: Option<Instant>,
+}
+
+impl ActivityThrottle {
+    pub const fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Whether an activity now is passed on
+    pub fn pass(&mut self) -> bool {
+        if self
+            .last
+            .is_some_and(|last| last.elapsed() < ACTIVITY_INTERVAL)
+        {
+            return false;
+        }
+        self.last = Some(Instant::now());
+        true
+    }
+}
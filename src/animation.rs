@@ -0,0 +1,166 @@
+//! Sprite animations of the pet on the status page.
+//!
+//! A [`Pet`] is a list of [`Rule`]s, the first rule matching the [`DisplayState`] picks the
+//! [`Animation`]. An animation is a sequence of any number of frames with their own durations,
+//! optionally played faster while typing. [`Animator`] plays the pet.
+use crate::displaypages::DisplayState;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::image::{Image, ImageRaw};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+
+pub struct Frame {
+    /// 1 bit per pixel, row by row, the leftmost pixel in the most significant bit
+    pub image: &'static [u8],
+    pub duration_ms: u32,
+}
+
+/// How the frame durations change with the typing speed
+pub enum Tempo {
+    /// Frames are shown as long as their duration
+    Fixed,
+    /// Frames are shown as long as their duration when not typing, and shortened linearly down
+    /// to `min_ms` at `full_wpm`
+    Wpm { full_wpm: u16, min_ms: u32 },
+}
+
+pub struct Animation {
+    /// Width of the frames in pixels, the height follows from the image length
+    pub width: u32,
+    pub frames: &'static [Frame],
+    pub tempo: Tempo,
+}
+
+impl Animation {
+    /// Height of the frames in pixels
+    pub fn height(&self) -> u32 {
+        self.frames
+            .first()
+            .map_or(0, |frame| frame.image.len() as u32 / self.width.div_ceil(8))
+    }
+
+    fn frame_duration(&self, frame: usize, wpm: u16) -> Duration {
+        let duration_ms = self.frames[frame].duration_ms;
+        let duration_ms = match self.tempo {
+            Tempo::Fixed => duration_ms,
+            Tempo::Wpm { full_wpm, min_ms } => {
+                let wpm = wpm.min(full_wpm) as u32;
+                let full_wpm = full_wpm.max(1) as u32;
+                duration_ms - (duration_ms.saturating_sub(min_ms)) * wpm / full_wpm
+            }
+        };
+        Duration::from_millis(duration_ms as u64)
+    }
+}
+
+pub enum Condition {
+    CapsLock,
+    Layer(u8),
+    /// WPM up to and including the value
+    WpmAtMost(u16),
+    Always,
+}
+
+impl Condition {
+    fn matches(&self, state: &DisplayState) -> bool {
+        match *self {
+            Condition::CapsLock => state.indicators.caps_lock(),
+            Condition::Layer(layer) => state.layer == layer,
+            Condition::WpmAtMost(wpm) => state.wpm <= wpm,
+            Condition::Always => true,
+        }
+    }
+}
+
+pub struct Rule {
+    pub when: Condition,
+    pub animation: &'static Animation,
+}
+
+pub struct Pet {
+    /// Checked in order, the first matching rule is played. End with [`Condition::Always`].
+    pub rules: &'static [Rule],
+}
+
+impl Pet {
+    fn animation(&self, state: &DisplayState) -> Option<&'static Animation> {
+        self.rules
+            .iter()
+            .find(|rule| rule.when.matches(state))
+            .map(|rule| rule.animation)
+    }
+}
+
+/// Plays a [`Pet`], switching its animation with the state
+pub struct Animator {
+    pet: &'static Pet,
+    animation: Option<&'static Animation>,
+    frame: usize,
+    frame_since: Instant,
+}
+
+impl Animator {
+    pub fn new(pet: &'static Pet) -> Self {
+        Self {
+            pet,
+            animation: None,
+            frame: 0,
+            frame_since: Instant::now(),
+        }
+    }
+
+    /// Size of the animation played in `state`, for the layout
+    pub fn size(&self, state: &DisplayState) -> Size {
+        self.pet.animation(state).map_or(Size::zero(), |animation| {
+            Size::new(animation.width, animation.height())
+        })
+    }
+
+    /// Whether a draw in `state` shows another frame than the last one
+    pub fn due(&self, state: &DisplayState) -> bool {
+        let Some(animation) = self.pet.animation(state) else {
+            return false;
+        };
+        match self.animation {
+            Some(current) if core::ptr::eq(current, animation) => {
+                animation.frames.len() > 1
+                    && self.frame_since.elapsed() >= animation.frame_duration(self.frame, state.wpm)
+            }
+            _ => !animation.frames.is_empty(),
+        }
+    }
+
+    /// Draws the current frame, advancing the animation first
+    pub fn draw<D>(
+        &mut self,
+        state: &DisplayState,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let Some(animation) = self.pet.animation(state) else {
+            return Ok(());
+        };
+        if animation.frames.is_empty() {
+            return Ok(());
+        }
+
+        let switched = self
+            .animation
+            .is_none_or(|current| !core::ptr::eq(current, animation));
+        if switched {
+            self.animation = Some(animation);
+            self.frame = 0;
+            self.frame_since = Instant::now();
+        } else if self.frame_since.elapsed() >= animation.frame_duration(self.frame, state.wpm) {
+            self.frame = (self.frame + 1) % animation.frames.len();
+            self.frame_since = Instant::now();
+        }
+
+        let image =
+            ImageRaw::<BinaryColor>::new(animation.frames[self.frame].image, animation.width);
+        Image::new(&image, position).draw(target)
+    }
+}
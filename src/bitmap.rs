@@ -0,0 +1,295 @@
+//! Conversions between the memory layouts of 1 bit per pixel bitmaps, usable at compile time.
+//!
+//! Also used by build.rs to pack the converted sprites, so this module only depends on `core`.
+
+/// Memory layout of a bitmap of any width and height
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// QMK's page layout of the SSD1306 buffer: pages of 8 rows, a byte is a column of a page
+    /// with the top pixel in the least significant bit. The last page is padded.
+    QmkPages,
+    /// Row by row, the leftmost pixel in the most significant bit, rows padded to whole bytes.
+    /// The layout of embedded-graphics' `ImageRaw<BinaryColor>`.
+    RowsMsb,
+    /// Row by row, the leftmost pixel in the least significant bit, rows padded to whole bytes
+    RowsLsb,
+}
+
+impl Layout {
+    /// Bytes of a `width` x `height` bitmap
+    pub const fn len(self, width: usize, height: usize) -> usize {
+        match self {
+            Layout::QmkPages => width * height.div_ceil(8),
+            Layout::RowsMsb | Layout::RowsLsb => width.div_ceil(8) * height,
+        }
+    }
+
+    /// Byte and bit of pixel `x`, `y`
+    const fn position(self, width: usize, x: usize, y: usize) -> (usize, u32) {
+        match self {
+            Layout::QmkPages => ((y / 8) * width + x, (y % 8) as u32),
+            Layout::RowsMsb => (y * width.div_ceil(8) + x / 8, 7 - (x % 8) as u32),
+            Layout::RowsLsb => (y * width.div_ceil(8) + x / 8, (x % 8) as u32),
+        }
+    }
+
+    pub const fn get(self, data: &[u8], width: usize, x: usize, y: usize) -> bool {
+        let (byte, bit) = self.position(width, x, y);
+        (data[byte] >> bit) & 1 != 0
+    }
+
+    pub const fn set(self, data: &mut [u8], width: usize, x: usize, y: usize, on: bool) {
+        let (byte, bit) = self.position(width, x, y);
+        if on {
+            data[byte] |= 1 << bit;
+        } else {
+            data[byte] &= !(1 << bit);
+        }
+    }
+}
+
+/// Rotation or mirroring applied while converting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    None,
+    /// A quarter turn clockwise, width and height swap
+    Rotate90,
+    Rotate180,
+    /// A quarter turn counterclockwise, width and height swap
+    Rotate270,
+    /// Left and right swap
+    MirrorX,
+    /// Top and bottom swap
+    MirrorY,
+}
+
+impl Transform {
+    /// Width and height of a `width` x `height` bitmap after the transform
+    pub const fn size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Rotate90 | Transform::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// Pixel of the `width` x `height` input shown at `x`, `y` of the output
+    const fn source(self, width: usize, height: usize, x: usize, y: usize) -> (usize, usize) {
+        match self {
+            Transform::None => (x, y),
+            Transform::Rotate90 => (y, height - 1 - x),
+            Transform::Rotate180 => (width - 1 - x, height - 1 - y),
+            Transform::Rotate270 => (width - 1 - y, x),
+            Transform::MirrorX => (width - 1 - x, y),
+            Transform::MirrorY => (x, height - 1 - y),
+        }
+    }
+}
+
+/// Converts the `width` x `height` bitmap `input` in the `from` layout into `output` in the `to`
+/// layout, transformed. Padding bits of `output` are cleared.
+pub const fn convert(
+    input: &[u8],
+    from: Layout,
+    output: &mut [u8],
+    to: Layout,
+    width: usize,
+    height: usize,
+    transform: Transform,
+) {
+    let (out_width, out_height) = transform.size(width, height);
+    assert!(input.len() == from.len(width, height), "input length");
+    assert!(
+        output.len() == to.len(out_width, out_height),
+        "output length"
+    );
+
+    let mut i = 0;
+    while i < output.len() {
+        output[i] = 0;
+        i += 1;
+    }
+    let mut y = 0;
+    while y < out_height {
+        let mut x = 0;
+        while x < out_width {
+            let (source_x, source_y) = transform.source(width, height, x, y);
+            if from.get(input, width, source_x, source_y) {
+                to.set(output, out_width, x, y, true);
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+}
+
+/// [`convert`] into a new array of `N` bytes
+pub const fn converted<const N: usize>(
+    input: &[u8],
+    from: Layout,
+    to: Layout,
+    width: usize,
+    height: usize,
+    transform: Transform,
+) -> [u8; N] {
+    let mut output = [0u8; N];
+    convert(input, from, &mut output, to, width, height, transform);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Layout::{QmkPages, RowsLsb, RowsMsb};
+    use super::Transform::{MirrorX, MirrorY, Rotate180, Rotate270, Rotate90};
+    use super::*;
+
+    // A 10 x 3 "L" with a dot:
+    //
+    //   X........X
+    //   X.........
+    //   XXXX......
+    const L_ROWS_MSB: [u8; 6] = [0x80, 0x40, 0x80, 0x00, 0xf0, 0x00];
+    const L_ROWS_LSB: [u8; 6] = [0x01, 0x02, 0x01, 0x00, 0x0f, 0x00];
+    const L_QMK_PAGES: [u8; 10] = [0x07, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+    #[test]
+    fn converts_between_layouts() {
+        assert_eq!(
+            converted::<10>(&L_ROWS_MSB, RowsMsb, QmkPages, 10, 3, Transform::None),
+            L_QMK_PAGES
+        );
+        assert_eq!(
+            converted::<6>(&L_QMK_PAGES, QmkPages, RowsMsb, 10, 3, Transform::None),
+            L_ROWS_MSB
+        );
+        assert_eq!(
+            converted::<6>(&L_ROWS_MSB, RowsMsb, RowsLsb, 10, 3, Transform::None),
+            L_ROWS_LSB
+        );
+    }
+
+    #[test]
+    fn pads_odd_sizes() {
+        // 5 x 11, the corners lit
+        let mut corners = [0u8; 11];
+        for (x, y) in [(0, 0), (4, 0), (0, 10), (4, 10)] {
+            RowsMsb.set(&mut corners, 5, x, y, true);
+        }
+        assert_eq!(corners, [0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x88]);
+        assert_eq!(QmkPages.len(5, 11), 10);
+        assert_eq!(
+            converted::<10>(&corners, RowsMsb, QmkPages, 5, 11, Transform::None),
+            [0x01, 0, 0, 0, 0x01, 0x04, 0, 0, 0, 0x04]
+        );
+        assert_eq!(
+            converted::<11>(&corners, RowsMsb, RowsLsb, 5, 11, Transform::None),
+            [0x11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x11]
+        );
+
+        // 9 x 2, rows take two bytes
+        assert_eq!(RowsMsb.len(9, 2), 4);
+        let mut corner = [0u8; 4];
+        RowsMsb.set(&mut corner, 9, 8, 1, true);
+        assert_eq!(corner, [0, 0, 0, 0x80]);
+        assert_eq!(
+            converted::<9>(&corner, RowsMsb, QmkPages, 9, 2, Transform::None),
+            [0, 0, 0, 0, 0, 0, 0, 0, 0x02]
+        );
+    }
+
+    #[test]
+    fn clears_padding_of_the_output() {
+        let mut output = [0xff; 11];
+        convert(
+            &[0x01, 0, 0, 0, 0x01, 0x04, 0, 0, 0, 0x04],
+            QmkPages,
+            &mut output,
+            RowsMsb,
+            5,
+            11,
+            Transform::None,
+        );
+        assert_eq!(output, [0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x88]);
+    }
+
+    #[test]
+    fn packs_pages_of_eight_rows() {
+        // 1 x 16, the first and last row of both pages lit
+        let mut column = [0u8; 16];
+        for y in [0, 7, 8, 15] {
+            column[y] = 0x80;
+        }
+        assert_eq!(
+            converted::<2>(&column, RowsMsb, QmkPages, 1, 16, Transform::None),
+            [0x81, 0x81]
+        );
+
+        // Pixel 3, 9 of a 4 pixel wide bitmap is in the second page, second bit
+        let mut pages = [0u8; 8];
+        QmkPages.set(&mut pages, 4, 3, 9, true);
+        assert_eq!(pages, [0, 0, 0, 0, 0, 0, 0, 0x02]);
+        assert!(QmkPages.get(&pages, 4, 3, 9));
+        QmkPages.set(&mut pages, 4, 3, 9, false);
+        assert_eq!(pages, [0; 8]);
+    }
+
+    #[test]
+    fn rotates_and_mirrors() {
+        assert_eq!(Rotate90.size(10, 3), (3, 10));
+        assert_eq!(Rotate180.size(10, 3), (10, 3));
+        assert_eq!(Rotate270.size(10, 3), (3, 10));
+
+        // Clockwise, 3 x 10
+        let rotated_90 = [0xe0, 0x80, 0x80, 0x80, 0, 0, 0, 0, 0, 0x20];
+        assert_eq!(
+            converted::<10>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, Rotate90),
+            rotated_90
+        );
+        assert_eq!(
+            converted::<10>(&L_QMK_PAGES, QmkPages, RowsMsb, 10, 3, Rotate90),
+            rotated_90
+        );
+        // Counterclockwise, 3 x 10
+        assert_eq!(
+            converted::<10>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, Rotate270),
+            [0x80, 0, 0, 0, 0, 0, 0x20, 0x20, 0x20, 0xe0]
+        );
+        assert_eq!(
+            converted::<6>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, Rotate180),
+            [0x03, 0xc0, 0x00, 0x40, 0x80, 0x40]
+        );
+        assert_eq!(
+            converted::<6>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, MirrorX),
+            [0x80, 0x40, 0x00, 0x40, 0x03, 0xc0]
+        );
+        assert_eq!(
+            converted::<6>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, MirrorY),
+            [0xf0, 0x00, 0x80, 0x00, 0x80, 0x40]
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let rotated = converted::<10>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, Rotate90);
+        assert_eq!(
+            converted::<6>(&rotated, RowsMsb, RowsMsb, 3, 10, Rotate270),
+            L_ROWS_MSB
+        );
+        let turned = converted::<6>(&L_ROWS_MSB, RowsMsb, RowsLsb, 10, 3, Rotate180);
+        assert_eq!(
+            converted::<6>(&turned, RowsLsb, RowsMsb, 10, 3, Rotate180),
+            L_ROWS_MSB
+        );
+        let flipped = converted::<10>(&L_QMK_PAGES, QmkPages, QmkPages, 10, 3, MirrorY);
+        assert_eq!(
+            converted::<10>(&flipped, QmkPages, QmkPages, 10, 3, MirrorY),
+            L_QMK_PAGES
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "output length")]
+    fn rejects_a_wrong_output_length() {
+        // A quarter turn needs 3 x 10 pixels, 10 bytes
+        converted::<6>(&L_ROWS_MSB, RowsMsb, RowsMsb, 10, 3, Rotate90);
+    }
+}
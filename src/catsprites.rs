@@ -0,0 +1,60 @@
+//! The typing cat, the pet of the `pet-cat` feature. Its frames are the images in
+//! `assets/sprites/cat_*`, converted by build.rs into [`crate::sprites`].
+use crate::animation::{Animation, Condition, Pet, Rule, Tempo};
+use crate::sprites::{CAT_IDLE, CAT_SHOUT, CAT_TAP, CAT_WAIT, CAT_WHISP};
+
+/// Typing speed up to which the cat sits idle
+const MIN_WALK_SPEED: u16 = 5;
+/// Typing speed from which the cat shouts, frames are shortest from here on
+const MIN_RUN_SPEED: u16 = 50;
+
+/// Shortens the frames of the moving animations while typing
+const TYPING_TEMPO: Tempo = Tempo::Wpm {
+    full_wpm: MIN_RUN_SPEED,
+    min_ms: 100,
+};
+
+/// Eyes open for a while, then a blink
+static IDLE: &Animation = &CAT_IDLE;
+static TAP: Animation = typing(&CAT_TAP);
+static SHOUT: Animation = typing(&CAT_SHOUT);
+static WAIT: Animation = typing(&CAT_WAIT);
+static WHISP: Animation = typing(&CAT_WHISP);
+
+/// `sprite` played faster while typing
+const fn typing(sprite: &'static Animation) -> Animation {
+    Animation {
+        width: sprite.width,
+        frames: sprite.frames,
+        tempo: TYPING_TEMPO,
+    }
+}
+
+pub static CAT: Pet = Pet {
+    rules: &[
+        Rule {
+            when: Condition::CapsLock,
+            animation: &SHOUT,
+        },
+        Rule {
+            when: Condition::Layer(1),
+            animation: &WHISP,
+        },
+        Rule {
+            when: Condition::Layer(2),
+            animation: &WAIT,
+        },
+        Rule {
+            when: Condition::WpmAtMost(MIN_WALK_SPEED),
+            animation: IDLE,
+        },
+        Rule {
+            when: Condition::WpmAtMost(MIN_RUN_SPEED),
+            animation: &TAP,
+        },
+        Rule {
+            when: Condition::Always,
+            animation: &SHOUT,
+        },
+    ],
+};
@@ -7,19 +7,25 @@ mod keymap;
 mod macros;
 mod vial;
 
+use core::cell::RefCell;
 use defmt::info;
 use embassy_executor::Spawner;
 use embassy_rp::bind_interrupts;
-use embassy_rp::flash::{Async, Flash};
+use embassy_embedded_hal::flash::partition::Partition;
+use embassy_rp::flash::{Async, Flash, ERASE_SIZE};
 use embassy_rp::gpio::Input;
-use embassy_rp::peripherals::{UART0, USB};
+use embassy_rp::peripherals::{FLASH, SPI0, UART0, USB};
 use embassy_rp::uart::{self, BufferedUart};
 use embassy_rp::usb::{Driver, InterruptHandler};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_time::{Duration, Timer};
 use rmk::config::{
     BehaviorConfig, DeviceConfig, MorsesConfig, PositionalConfig, RmkConfig, StorageConfig,
     VialConfig,
 };
 use rmk::debounce::default_debouncer::DefaultDebouncer;
+use rmk::event::publish_event;
 use rmk::heapless::Vec;
 use rmk::input_device::Runnable;
 use rmk::join_all;
@@ -32,10 +38,51 @@ use rmk::{initialize_keymap_and_storage, run_all, run_rmk};
 use static_cell::StaticCell;
 use vial::{VIAL_KEYBOARD_DEF, VIAL_KEYBOARD_ID};
 use {defmt_rtt as _, panic_probe as _};
-pub mod pmw3360srom;
+pub mod sensorconfig;
+pub mod motiontrace;
+pub mod sensorbus;
+use sensorbus::SensorBus;
+pub mod sensormonitor;
+use sensormonitor::SensorMonitor;
+pub mod sensorstate;
+pub mod activitystate;
+#[cfg(feature = "hid-query")]
+pub mod hidprotocol;
+#[cfg(feature = "hid-query")]
+pub mod hidquery;
+#[cfg(feature = "hid-query")]
+use hidquery::HidQuery;
+pub mod linkstate;
+pub mod usbstate;
+pub mod usbmonitor;
+use usbmonitor::UsbMonitor;
+pub mod viallock;
+use viallock::{VialLockTracker, VIAL_UNLOCK_KEYS};
+pub mod splitframe;
+pub mod splitlink;
+use splitlink::{SplitForwarder, SplitLink, SplitLinkWriter, SplitTx};
+pub mod displaycommands;
+use displaycommands::DisplayCommandController;
+pub mod displaysettings;
+use displaysettings::DisplaySettingsController;
+pub mod flashstore;
+use flashstore::FlashStore;
+pub mod typingstate;
+use typingstate::TypingCounters;
+pub mod typingstats;
+use typingstats::TypingStats;
+pub mod modifierstate;
+pub mod modifiertracker;
+use modifiertracker::ModifierTracker;
+pub mod notification;
+use notification::{Notification, NotificationEvent, NOTIFICATION_TTL};
+pub mod pointingstate;
 
 pub mod pointingdevcontroller;
 use crate::pointingdevcontroller::PointingDeviceController;
+pub mod scrollmotion;
+pub mod scrollcontroller;
+use scrollcontroller::ScrollController;
 pub mod jigglemode;
 use jigglemode::JiggleController;
 
@@ -45,6 +92,8 @@ bind_interrupts!(struct Irqs {
 });
 
 const FLASH_SIZE: usize = 2 * 1024 * 1024;
+/// Time after boot until a storage reset is shown, the peripheral has to come up first
+const STORAGE_NOTICE_DELAY: Duration = Duration::from_secs(3);
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
@@ -65,6 +114,10 @@ async fn main(_spawner: Spawner) {
     // Both blocking and async flash are support, use different API
     // let flash = Flash::<_, Blocking, FLASH_SIZE>::new_blocking(p.FLASH);
     let flash = Flash::<_, Async, FLASH_SIZE>::new(p.FLASH, p.DMA_CH0);
+    // Shared by rmk's storage and our own records
+    static FLASH_MUTEX: StaticCell<Mutex<CriticalSectionRawMutex, Flash<'static, FLASH, Async, FLASH_SIZE>>> =
+        StaticCell::new();
+    let flash = &*FLASH_MUTEX.init(Mutex::new(flash));
 
     let keyboard_device_config = DeviceConfig {
         vid: 0x44dd,
@@ -74,7 +127,7 @@ async fn main(_spawner: Spawner) {
         serial_number: "vial:f64c2b3c:000001",
     };
 
-    let vial_config = VialConfig::new(VIAL_KEYBOARD_ID, VIAL_KEYBOARD_DEF, &[(0, 0), (2, 0)]); // Keys at (row=0,col=0) and (row=2,col=0) (~ and ESC)
+    let vial_config = VialConfig::new(VIAL_KEYBOARD_ID, VIAL_KEYBOARD_DEF, &VIAL_UNLOCK_KEYS);
 
     let rmk_config = RmkConfig {
         device_config: keyboard_device_config,
@@ -95,7 +148,10 @@ async fn main(_spawner: Spawner) {
         rx_buf,
         uart::Config::default(),
     );
-    use embassy_time::Duration;
+    // rmk reads through the split link, its frames and ours share the sending half
+    let (uart_tx, uart_rx) = uart_receiver.split();
+    let split_tx = SplitTx::new(uart_tx);
+    let mut split_writer = SplitLinkWriter::new(&split_tx);
 
     // Initialize the storage and keymap
     let mut default_keymap = keymap::get_default_keymap();
@@ -123,9 +179,20 @@ async fn main(_spawner: Spawner) {
     // ..Default::default()
     // };
     let mut per_key_config = PositionalConfig::default();
+
+    // rmk keeps its storage in the last sectors of the flash, our records go into the sector below
+    let record_offset = (FLASH_SIZE - (storage_config.num_sectors as usize + 1) * ERASE_SIZE) as u32;
+    let record_flash = Partition::new(flash, record_offset, ERASE_SIZE as u32);
+    let rmk_flash = Partition::new(flash, 0, FLASH_SIZE as u32);
+    // The typing statistics get the sector below, they are saved more often
+    let stats_flash = Partition::new(flash, record_offset - ERASE_SIZE as u32, ERASE_SIZE as u32);
+    // Each store compacts into a spare sector, the two below the first sectors
+    let record_spare_flash = Partition::new(flash, record_offset - 2 * ERASE_SIZE as u32, ERASE_SIZE as u32);
+    let stats_spare_flash = Partition::new(flash, record_offset - 3 * ERASE_SIZE as u32, ERASE_SIZE as u32);
+
     let (keymap, mut storage) = initialize_keymap_and_storage(
         &mut default_keymap,
-        flash,
+        rmk_flash,
         &storage_config,
         &mut behavior_config,
         &mut per_key_config,
@@ -141,7 +208,8 @@ async fn main(_spawner: Spawner) {
     // use embassy_embedded_hal::adapter::BlockingAsync;
     use embassy_rp::gpio::{Level, Output};
     use embassy_rp::spi::{Config, Phase, Polarity, Spi};
-    use rmk::input_device::pmw33xx::{Pmw3360Spec, Pmw33xx, Pmw33xxConfig};
+    use rmk::input_device::pmw33xx::{Pmw33xx, Pmw33xxConfig};
+    use sensorconfig::SensorSpec;
     use rmk::input_device::pointing::PointingDevice;
 
     let mut spi_cfg = Config::default();
@@ -170,25 +238,30 @@ async fn main(_spawner: Spawner) {
     // let pmw3360_spi = Spi::new_blocking(p.SPI0, pmw3360_sck, pmw3360_mosi, pmw3360_miso, spi_cfg);
     // let pmw3360_spi = BlockingAsync::new(pmw3360_spi);
 
+    // Share the bus with the sensor monitor, which verifies the SROM after upload
+    static SENSOR_BUS: StaticCell<SensorBus<Spi<'static, SPI0, embassy_rp::spi::Async>, Output<'static>>> =
+        StaticCell::new();
+    let sensor_bus = &*SENSOR_BUS.init(SensorBus::new(pmw3360_spi, pmw3360_cs));
+
     // Initialize PMW3360 mouse sensor
     let pmw3360_config = Pmw33xxConfig {
-        res_cpi: 1600,
+        res_cpi: sensorconfig::clamp_cpi(1600),
         rot_trans_angle: -15,
         liftoff_dist: 0x08,
     };
 
     // Create the sensor device
-    let mut pmw3360_device = PointingDevice::<Pmw33xx<_, _, _, Pmw3360Spec>>::new_with_firmware_poll_interval_report_hertz(
+    let mut pmw3360_device = PointingDevice::<Pmw33xx<_, _, _, SensorSpec>>::new_with_firmware_poll_interval_report_hertz(
         0,
-        pmw3360_spi,
-        pmw3360_cs,
+        sensor_bus.device(),
+        sensor_bus.cs(),
 
         // Some(pmw3360_irq),
         None::<::embassy_rp::gpio::Input<'static>>,
         pmw3360_config,
         500,
         125,
-        crate::pmw3360srom::PMW3360_SROM,
+        crate::sensorconfig::SENSOR_SROM,
     );
 
     use rmk::input_device::pointing::{PointingProcessor, PointingProcessorConfig};
@@ -202,21 +275,68 @@ async fn main(_spawner: Spawner) {
 
     // Initialize pointing device controller
     // this is for detecting layer changes and sending controller events to the PMW3360
-    let mut pointing_controller = PointingDeviceController::default();
+    let mut pointing_controller = PointingDeviceController::new(&keymap);
+    // Wheel and pan reports while the layer scrolls
+    let mut scroll_controller = ScrollController::new();
 
     // Jiggle control
     let mut jiggle_controller = JiggleController::new(&keymap);
 
+    // SROM verification and image quality readout
+    let mut sensor_monitor = SensorMonitor::new(sensor_bus);
+    // Key press counts, kept by the typing stats and read by the host for the heatmap
+    let typing_counters = RefCell::new(TypingCounters::new());
+    // Sensor diagnostics and typing stats for the host, over Vial's raw HID
+    #[cfg(feature = "hid-query")]
+    let mut hid_query = HidQuery::new(&typing_counters);
+    #[cfg(feature = "hid-query")]
+    let hid_query_task = run_all!(hid_query);
+    #[cfg(not(feature = "hid-query"))]
+    let hid_query_task = async {};
+
+    // USB state for the display
+    let mut usb_monitor = UsbMonitor::new();
+    let mut vial_lock_tracker = VialLockTracker::new();
+
+    // Display keys, the display itself is on the peripheral
+    let mut display_command_controller = DisplayCommandController::new(&keymap);
+    let mut split_forwarder = SplitForwarder::new();
+    // Contrast and display on/off, kept in flash
+    let mut display_settings_controller = DisplaySettingsController::new(FlashStore::new(record_flash, record_spare_flash));
+    // Key press counts, kept in flash and shown on the display
+    let mut typing_stats = TypingStats::new(&typing_counters, FlashStore::new(stats_flash, stats_spare_flash));
+    // Held modifiers for the display
+    let mut modifier_tracker = ModifierTracker::new(&keymap);
+    // rmk cleared its storage at boot, tell once the display is likely up
+    let storage_notice = async {
+        if storage_config.clear_storage {
+            Timer::after(STORAGE_NOTICE_DELAY).await;
+            publish_event(NotificationEvent(Notification::new("STORAGE RESET", NOTIFICATION_TTL)));
+        }
+    };
+
     join_all!(
         run_all!(
             matrix,
             jiggle_controller,
+            display_command_controller,
+            display_settings_controller,
+            typing_stats,
+            modifier_tracker,
+            vial_lock_tracker,
+            split_forwarder,
             pointing_controller,
+            scroll_controller,
             pmw3360_device,
             pmw3360_processor
         ),
         keyboard.run(),
-        run_peripheral_manager::<6, 6, 0, 0, _>(0, uart_receiver),
+        sensor_monitor.run(),
+        usb_monitor.run(),
+        split_writer.run(),
+        storage_notice,
+        hid_query_task,
+        run_peripheral_manager::<6, 6, 0, 0, _>(0, SplitLink::new(uart_rx, &split_tx)),
         run_rmk(&keymap, driver, &mut storage, rmk_config)
     )
     .await;
@@ -0,0 +1,105 @@
+use core::cell::RefCell;
+use defmt::info;
+use rmk::event::publish_event;
+use rmk::event::KeyboardEvent;
+use rmk::event::LayerChangeEvent;
+use rmk::keymap::KeyMap;
+use rmk::types::action::Action;
+use rmk::types::action::KeyAction;
+use rmk_macro::event;
+use rmk_macro::processor;
+
+/// Keymap `User` action of the diagnostics toggle
+pub const USER_DIAGNOSTICS: u8 = 1;
+/// Keymap `User` actions switching the display pages
+pub const USER_NEXT_PAGE: u8 = 2;
+pub const USER_PREV_PAGE: u8 = 3;
+pub const USER_CAROUSEL: u8 = 4;
+/// Keymap `User` actions of the display brightness
+pub const USER_CONTRAST_UP: u8 = 5;
+pub const USER_CONTRAST_DOWN: u8 = 6;
+pub const USER_DISPLAY: u8 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum DisplayCommand {
+    /// Switch between the status page and the pointing device page
+    ToggleDiagnostics,
+    NextPage,
+    PrevPage,
+    /// Switch the pages automatically
+    ToggleCarousel,
+    ContrastUp,
+    ContrastDown,
+    /// Switch the display on or off
+    ToggleDisplay,
+}
+
+impl DisplayCommand {
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(DisplayCommand::ToggleDiagnostics),
+            1 => Some(DisplayCommand::NextPage),
+            2 => Some(DisplayCommand::PrevPage),
+            3 => Some(DisplayCommand::ToggleCarousel),
+            4 => Some(DisplayCommand::ContrastUp),
+            5 => Some(DisplayCommand::ContrastDown),
+            6 => Some(DisplayCommand::ToggleDisplay),
+            _ => None,
+        }
+    }
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayCommandEvent(pub DisplayCommand);
+
+/// Turns the display related `User` actions of the keymap into [`DisplayCommandEvent`]s.
+/// The keymap lives on the central, the display on the peripheral.
+#[processor(subscribe = [LayerChangeEvent, KeyboardEvent])]
+pub struct DisplayCommandController<
+    'a,
+    const ROW: usize,
+    const COL: usize,
+    const NUM_LAYER: usize,
+    const NUM_ENCODER: usize,
+> {
+    current_layer: u8,
+    keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>,
+}
+
+impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, const NUM_ENCODER: usize>
+    DisplayCommandController<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>
+{
+    pub fn new(keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>) -> Self {
+        Self {
+            current_layer: 0,
+            keymap,
+        }
+    }
+
+    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
+        self.current_layer = event.layer;
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        if !event.pressed {
+            return;
+        }
+        let action = self
+            .keymap
+            .borrow()
+            .get_action_at(event.pos, self.current_layer as usize);
+        let command = match action {
+            KeyAction::Single(Action::User(USER_DIAGNOSTICS)) => DisplayCommand::ToggleDiagnostics,
+            KeyAction::Single(Action::User(USER_NEXT_PAGE)) => DisplayCommand::NextPage,
+            KeyAction::Single(Action::User(USER_PREV_PAGE)) => DisplayCommand::PrevPage,
+            KeyAction::Single(Action::User(USER_CAROUSEL)) => DisplayCommand::ToggleCarousel,
+            KeyAction::Single(Action::User(USER_CONTRAST_UP)) => DisplayCommand::ContrastUp,
+            KeyAction::Single(Action::User(USER_CONTRAST_DOWN)) => DisplayCommand::ContrastDown,
+            KeyAction::Single(Action::User(USER_DISPLAY)) => DisplayCommand::ToggleDisplay,
+            _ => return,
+        };
+        info!("Display command {}", command);
+        publish_event(DisplayCommandEvent(command));
+    }
+}
@@ -0,0 +1,35 @@
+// Display controller, size, rotation and timeouts are generated by `build.rs` from `[display]`
+// in keyboard.toml
+include!(concat!(env!("OUT_DIR"), "/display_generated.rs"));
+
+use crate::oled::{Controller, Rotation};
+use embassy_time::Duration;
+
+#[cfg(display_controller = "ssd1306")]
+pub const DISPLAY_CONTROLLER: Controller = Controller::Ssd1306;
+#[cfg(display_controller = "sh1106")]
+pub const DISPLAY_CONTROLLER: Controller = Controller::Sh1106;
+#[cfg(display_controller = "ssd1309")]
+pub const DISPLAY_CONTROLLER: Controller = Controller::Ssd1309;
+
+pub const DISPLAY_ROTATION: Rotation = match DISPLAY_ROTATION_DEGREES {
+    0 => Rotation::Rotate0,
+    90 => Rotation::Rotate90,
+    180 => Rotation::Rotate180,
+    _ => Rotation::Rotate270,
+};
+
+/// Inactivity until the display is dimmed, `None` if it never is
+pub const DIM_TIMEOUT: Option<Duration> = seconds(DISPLAY_DIM_TIMEOUT_SECS);
+/// Inactivity until the display is switched off, `None` if it never is
+pub const OFF_TIMEOUT: Option<Duration> = seconds(DISPLAY_OFF_TIMEOUT_SECS);
+/// Interval of moving the content against burn-in, `None` if it never is
+pub const SHIFT_INTERVAL: Option<Duration> = seconds(DISPLAY_SHIFT_INTERVAL_SECS);
+
+const fn seconds(secs: u32) -> Option<Duration> {
+    if secs == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(secs as u64))
+    }
+}
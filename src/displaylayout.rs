@@ -0,0 +1,77 @@
+//! Layout of the display pages for any display size and rotation.
+//!
+//! Pages are designed for a column [`COLUMN_WIDTH`] pixels wide, the 128x32 display rotated. A
+//! [`Flow`] places the blocks of a page one below the other and continues in the next column
+//! when the current one is full, so a wider display shows a page in several columns side by
+//! side. Blocks that fit nowhere are left out.
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+
+/// Width the pages are designed for, the text of a line fits into it
+pub const COLUMN_WIDTH: u32 = 32;
+
+pub struct Flow {
+    size: Size,
+    columns: u32,
+    column_width: u32,
+    column: u32,
+    /// Top of the next block in the current column
+    y: i32,
+}
+
+impl Flow {
+    /// Flow over a whole display, or any other `DrawTarget`, of `size`
+    pub fn new(size: Size) -> Self {
+        let columns = (size.width / COLUMN_WIDTH).max(1);
+        Self {
+            size,
+            columns,
+            column_width: size.width / columns,
+            column: 0,
+            y: 0,
+        }
+    }
+
+    pub fn column_width(&self) -> u32 {
+        self.column_width
+    }
+
+    /// Top left corner of the next block of `height` pixels, `None` if it doesn't fit anymore
+    pub fn place(&mut self, height: i32) -> Option<Point> {
+        let column_height = self.size.height as i32;
+        if self.y > 0 && self.y + height > column_height {
+            self.column += 1;
+            self.y = 0;
+        }
+        if self.column >= self.columns || height > column_height {
+            return None;
+        }
+        let position = Point::new((self.column * self.column_width) as i32, self.y);
+        self.y += height;
+        Some(position)
+    }
+
+    /// The next block of `height` pixels, as wide as a column
+    pub fn place_area(&mut self, height: i32) -> Option<Rectangle> {
+        let position = self.place(height)?;
+        Some(Rectangle::new(
+            position,
+            Size::new(self.column_width, height as u32),
+        ))
+    }
+
+    /// A block of `height` pixels at the bottom of the current column, or of the next one if it
+    /// doesn't fit. The column is full afterwards.
+    pub fn place_bottom(&mut self, height: i32) -> Option<Point> {
+        let position = self.place(height)?;
+        self.y = self.size.height as i32;
+        Some(Point::new(position.x, self.y - height))
+    }
+
+    /// Leaves `height` pixels empty, unless at the top of a column
+    pub fn skip(&mut self, height: i32) {
+        if self.y > 0 {
+            self.y += height;
+        }
+    }
+}
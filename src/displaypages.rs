@@ -0,0 +1,818 @@
+//! Pages of the OLED on the peripheral.
+//!
+//! [`Ssd1306Controller`] collects everything shown into a [`DisplayState`] and draws the
+//! current [`PageId`] with [`Pages::draw`]. Every page is a [`Widget`], which renders into
+//! any `DrawTarget`, not only into the display.
+//!
+//! [`Ssd1306Controller`]: crate::ssd1306cont::Ssd1306Controller
+use crate::animation::{Animator, Pet};
+use crate::displaylayout::Flow;
+use crate::displaysettings::DisplaySettings;
+use crate::linkstate::LinkStatus;
+use crate::modifierstate::Modifiers;
+use crate::notification::Notification;
+use crate::pointingstate::PointingState;
+use crate::sensorstate::{SensorDiag, SensorStatus};
+use crate::typingstate::TypingSummary;
+use crate::usbstate::UsbState;
+use crate::wpmhistory::WpmHistory;
+use core::fmt::Write;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::mono_font::MonoFont;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_4X6, ascii::FONT_6X10, MonoTextStyleBuilder},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use rmk::heapless::String;
+use rmk::types::led_indicator::LedIndicator;
+
+// Names of the `[[layer]]`s in keyboard.toml, abbreviated to the display width by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/layer_names_generated.rs"));
+// Legends of the peripheral's half of every layer in keyboard.toml, generated by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/keymap_legends_generated.rs"));
+
+const FONT: MonoFont<'_> = FONT_6X10;
+const FONT_SMALL: MonoFont<'_> = FONT_4X6;
+
+const TEXT_NORM: MonoTextStyle<'_, BinaryColor> = MonoTextStyleBuilder::new()
+    .font(&FONT)
+    .text_color(BinaryColor::On)
+    .build();
+const TEXT_INV: MonoTextStyle<'_, BinaryColor> = MonoTextStyleBuilder::new()
+    .font(&FONT)
+    .text_color(BinaryColor::Off)
+    .background_color(BinaryColor::On)
+    .build();
+const TEXT_SMALL: MonoTextStyle<'_, BinaryColor> = MonoTextStyleBuilder::new()
+    .font(&FONT_SMALL)
+    .text_color(BinaryColor::On)
+    .build();
+const TEXT_SMALL_INV: MonoTextStyle<'_, BinaryColor> = MonoTextStyleBuilder::new()
+    .font(&FONT_SMALL)
+    .text_color(BinaryColor::Off)
+    .background_color(BinaryColor::On)
+    .build();
+
+const LINE_HEIGHT: i32 = FONT.character_size.height as i32 + 2;
+const LINE_HEIGHT_SMALL: i32 = FONT_SMALL.character_size.height as i32 + 1;
+
+/// Everything shown on the display
+pub struct DisplayState {
+    pub indicators: LedIndicator,
+    pub layer: u8,
+    pub jiggle_active: bool,
+    /// Modifiers held on the keyboard
+    pub modifiers: Modifiers,
+    pub wpm: u16,
+    /// Highest WPM since boot
+    pub wpm_peak: u16,
+    pub wpm_history: WpmHistory,
+    /// Key press counts of the central
+    pub typing: TypingSummary,
+    pub sensor_status: Option<SensorStatus>,
+    pub sensor_diag: SensorDiag,
+    /// Split link to the central, `None` until it connected or timed out
+    pub link: Option<LinkStatus>,
+    /// USB connection of the central to the host, `None` until it was sent
+    pub usb: Option<UsbState>,
+    /// CPI and mode of the trackball, set on the central
+    pub pointing: PointingState,
+    /// Pages are switched automatically
+    pub carousel: bool,
+    pub contrast: u8,
+    /// Failed transfers to the display since boot
+    pub display_errors: u32,
+    /// Shown over the current page
+    pub notification: Option<Notification>,
+}
+
+impl Default for DisplayState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayState {
+    pub fn new() -> Self {
+        Self {
+            indicators: 0.into(),
+            layer: 0,
+            jiggle_active: false,
+            modifiers: Modifiers::default(),
+            wpm: 0,
+            wpm_peak: 0,
+            wpm_history: WpmHistory::new(),
+            typing: TypingSummary::default(),
+            sensor_status: None,
+            sensor_diag: SensorDiag::default(),
+            link: None,
+            usb: None,
+            pointing: PointingState::default(),
+            carousel: false,
+            contrast: DisplaySettings::default().contrast,
+            display_errors: 0,
+            notification: None,
+        }
+    }
+}
+
+/// Something that draws the [`DisplayState`], or a part of it
+pub trait Widget {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum PageId {
+    /// Lock indicators, layer, WPM and the cat
+    Status,
+    Stats,
+    /// Key presses and typing time counted on the central
+    Typing,
+    /// Trackball mode, CPI, sensor status and image quality
+    Pointing,
+    /// Legends of the current layer
+    Keymap,
+    Settings,
+}
+
+impl PageId {
+    /// Order of the pages when switching
+    const ALL: [PageId; 6] = [
+        PageId::Status,
+        PageId::Stats,
+        PageId::Typing,
+        PageId::Pointing,
+        PageId::Keymap,
+        PageId::Settings,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|page| *page == self).unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// All pages of the display
+pub struct Pages {
+    status: StatusPage,
+    stats: StatsPage,
+    typing: TypingPage,
+    pointing: PointingPage,
+    keymap: KeymapPage,
+    settings: SettingsPage,
+    toast: Toast,
+}
+
+impl Default for Pages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pages {
+    pub fn new() -> Self {
+        Self {
+            status: StatusPage::new(),
+            stats: StatsPage {
+                graph: WpmGraph,
+                uptime_mins: 0,
+            },
+            typing: TypingPage,
+            pointing: PointingPage,
+            keymap: KeymapPage,
+            settings: SettingsPage,
+            toast: Toast,
+        }
+    }
+
+    pub fn draw<D>(
+        &mut self,
+        page: PageId,
+        state: &DisplayState,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        match page {
+            PageId::Status => self.status.draw(state, target)?,
+            PageId::Stats => self.stats.draw(state, target)?,
+            PageId::Typing => self.typing.draw(state, target)?,
+            PageId::Pointing => self.pointing.draw(state, target)?,
+            PageId::Keymap => self.keymap.draw(state, target)?,
+            PageId::Settings => self.settings.draw(state, target)?,
+        }
+        self.toast.draw(state, target)
+    }
+
+    /// Whether `page` looks different than when it was last drawn, while the state is the same
+    pub fn due(&self, page: PageId, state: &DisplayState) -> bool {
+        match page {
+            PageId::Status => self.status.pet.as_ref().is_some_and(|pet| pet.due(state)),
+            PageId::Stats => self.stats.uptime_mins != StatsPage::uptime_mins(),
+            PageId::Typing | PageId::Pointing | PageId::Keymap | PageId::Settings => false,
+        }
+    }
+}
+
+fn draw_text<D>(
+    text: &str,
+    position: Point,
+    style: MonoTextStyle<'_, BinaryColor>,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    Text::with_baseline(text, position, style, Baseline::Top).draw(target)?;
+    Ok(())
+}
+
+/// Draws a line of text at the next place of `flow`
+fn draw_line<D>(
+    flow: &mut Flow,
+    text: &str,
+    style: MonoTextStyle<'_, BinaryColor>,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let line_height = if style.font.character_size == FONT.character_size {
+        LINE_HEIGHT
+    } else {
+        LINE_HEIGHT_SMALL
+    };
+    match flow.place(line_height) {
+        Some(position) => draw_text(text, position, style, target),
+        None => Ok(()),
+    }
+}
+
+/// Draws `widget` into `area` of `target`, as if the area was all of the target
+fn draw_widget<W, D>(
+    widget: &mut W,
+    state: &DisplayState,
+    area: Rectangle,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    W: Widget,
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let mut translated = target.translated(area.top_left);
+    widget.draw(
+        state,
+        &mut translated.clipped(&Rectangle::new(Point::zero(), area.size)),
+    )
+}
+
+/// Draws labels next to each other, inverted if their flag is set
+fn draw_flags<D>(flags: &[(&str, bool)], position: Point, target: &mut D) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let char_width = FONT.character_size.width as i32;
+    let mut cursor = position;
+    for (label, active) in flags {
+        let style = if *active { TEXT_INV } else { TEXT_NORM };
+        draw_text(label, cursor, style, target)?;
+        // One space between the labels
+        cursor.x += char_width * (label.len() as i32 + 1);
+    }
+    Ok(())
+}
+
+/// Draws single letter labels of the small font, inverted if their flag is set
+fn draw_small_flags<D>(
+    flags: &[(&str, bool)],
+    position: Point,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    // Two pixels between the labels, a space of the small font doesn't fit five of them
+    let step = FONT_SMALL.character_size.width as i32 + 2;
+    for (i, (label, active)) in flags.iter().enumerate() {
+        let style = if *active { TEXT_SMALL_INV } else { TEXT_SMALL };
+        let label_position = position + Point::new(i as i32 * step, 0);
+        draw_text(label, label_position, style, target)?;
+    }
+    Ok(())
+}
+
+fn layer_name(layer: u8) -> &'static str {
+    LAYER_NAMES.get(layer as usize).copied().unwrap_or("UNKNO")
+}
+
+/// The pet of the status page, chosen by the `pet-*` features
+#[cfg(feature = "pet-cat")]
+const PET: Option<&Pet> = Some(&crate::catsprites::CAT);
+#[cfg(not(feature = "pet-cat"))]
+const PET: Option<&Pet> = None;
+
+/// The original screen: lock indicators, layer, compose and jiggle, modifiers, WPM and the pet
+pub struct StatusPage {
+    pet: Option<Animator>,
+}
+
+impl StatusPage {
+    fn new() -> Self {
+        Self {
+            pet: PET.map(Animator::new),
+        }
+    }
+
+    /// Warns of a dropped split link, the host not taking keys, an unlocked Vial or a trackball
+    /// out of order
+    fn draw_alert<D>(state: &DisplayState, position: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        // Everything else shown is stale without the link, so it goes first
+        if state.link.is_some_and(|link| !link.connected) {
+            return draw_text("LINK!", position, TEXT_INV, target);
+        }
+        let usb_text = match state.usb {
+            None | Some(UsbState::Configured) => None,
+            Some(UsbState::Detached) => Some("NOUSB"),
+            Some(UsbState::Powered) => Some("USB?"),
+            Some(UsbState::Suspended) => Some("SLEEP"),
+            Some(UsbState::VialUnlocked) => Some("VIAL"),
+        };
+        if let Some(text) = usb_text {
+            return draw_text(text, position, TEXT_INV, target);
+        }
+        let text = match state.sensor_status {
+            None | Some(SensorStatus::Ok) => return Ok(()),
+            Some(SensorStatus::NoResponse) => "BALL?",
+            Some(_) => "BALL!",
+        };
+        draw_text(text, position, TEXT_INV, target)
+    }
+}
+
+impl Widget for StatusPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+
+        let indicators = &state.indicators;
+        if let Some(position) = flow.place(LINE_HEIGHT) {
+            draw_flags(
+                &[
+                    ("N", indicators.num_lock()),
+                    ("C", indicators.caps_lock()),
+                    ("S", indicators.scroll_lock()),
+                ],
+                position,
+                target,
+            )?;
+        }
+
+        draw_line(&mut flow, layer_name(state.layer), TEXT_NORM, target)?;
+
+        if let Some(position) = flow.place(LINE_HEIGHT) {
+            draw_flags(
+                &[("CO", indicators.compose()), ("JI", state.jiggle_active)],
+                position,
+                target,
+            )?;
+        }
+
+        let modifiers = &state.modifiers;
+        if let Some(position) = flow.place(LINE_HEIGHT_SMALL + 2) {
+            draw_small_flags(
+                &[
+                    ("C", modifiers.ctrl),
+                    ("S", modifiers.shift),
+                    ("A", modifiers.alt),
+                    ("G", modifiers.gui),
+                    ("H", modifiers.tap_hold),
+                ],
+                position,
+                target,
+            )?;
+        }
+
+        let mut wpm_text: String<16> = String::new();
+        write!(wpm_text, "W:{:>3}", state.wpm).unwrap();
+        draw_line(&mut flow, &wpm_text, TEXT_NORM, target)?;
+
+        if let Some(position) = flow.place(LINE_HEIGHT) {
+            Self::draw_alert(state, position, target)?;
+        }
+
+        // At the bottom, below everything else
+        let Some(pet) = &mut self.pet else {
+            return Ok(());
+        };
+        match flow.place_bottom(pet.size(state).height as i32) {
+            Some(position) => pet.draw(state, position, target),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sparkline of the WPM history, the latest sample at the right edge. Scaled to the highest
+/// sample shown.
+pub struct WpmGraph;
+
+impl WpmGraph {
+    const HEIGHT: u32 = 24;
+    /// Lowest WPM of the full height, so slow typing doesn't look like a sprint
+    const MIN_SCALE: u16 = 20;
+}
+
+impl Widget for WpmGraph {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let history = &state.wpm_history;
+        let width = target.bounding_box().size.width as i32;
+        let bottom = Self::HEIGHT as i32 - 1;
+        let scale = history.iter().fold(Self::MIN_SCALE, u16::max) as i32;
+        let style = PrimitiveStyle::with_stroke(BinaryColor::On, 1);
+
+        // Baseline
+        Line::new(Point::new(0, bottom), Point::new(width - 1, bottom))
+            .into_styled(style)
+            .draw(target)?;
+
+        let first_x = width - history.len() as i32;
+        let mut previous: Option<Point> = None;
+        for (i, wpm) in history.iter().enumerate() {
+            let point = Point::new(first_x + i as i32, bottom - wpm as i32 * bottom / scale);
+            Line::new(previous.unwrap_or(point), point)
+                .into_styled(style)
+                .draw(target)?;
+            previous = Some(point);
+        }
+        Ok(())
+    }
+}
+
+/// Typing statistics of the current session
+pub struct StatsPage {
+    graph: WpmGraph,
+    /// Uptime shown by the last draw
+    uptime_mins: u64,
+}
+
+impl StatsPage {
+    fn uptime_mins() -> u64 {
+        Instant::now().as_secs() / 60
+    }
+}
+
+impl Widget for StatsPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+        draw_line(&mut flow, "STATS", TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        self.uptime_mins = Self::uptime_mins();
+        let mut text: String<16> = String::new();
+        let lines = [
+            ("WPM", state.wpm),
+            ("PK", state.wpm_peak),
+            ("AV", state.wpm_history.average()),
+        ];
+        for (label, value) in lines {
+            text.clear();
+            write!(text, "{}:{:>5}", label, value).unwrap();
+            draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        }
+
+        flow.skip(LINE_HEIGHT_SMALL);
+        if let Some(area) = flow.place_area(WpmGraph::HEIGHT as i32) {
+            draw_widget(&mut self.graph, state, area, target)?;
+        }
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        draw_line(&mut flow, "UPTIME", TEXT_SMALL, target)?;
+        text.clear();
+        write!(
+            text,
+            "{:>3}:{:02}",
+            self.uptime_mins / 60,
+            self.uptime_mins % 60
+        )
+        .unwrap();
+        draw_line(&mut flow, &text, TEXT_SMALL, target)
+    }
+}
+
+/// Key presses and typing time counted on the central
+pub struct TypingPage;
+
+impl TypingPage {
+    /// Formats a count into the width of the display
+    fn count(value: u32) -> String<8> {
+        let mut text = String::new();
+        match value {
+            0..100_000 => write!(text, "{:>7}", value),
+            100_000..100_000_000 => write!(text, "{:>6}K", value / 1000),
+            _ => write!(text, "{:>6}M", value / 1_000_000),
+        }
+        .unwrap();
+        text
+    }
+}
+
+impl Widget for TypingPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+        draw_line(&mut flow, "KEYS", TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        let typing = &state.typing;
+        for (label, value) in [
+            ("TOTAL", typing.total_presses),
+            ("SESSION", typing.session_presses),
+        ] {
+            draw_line(&mut flow, label, TEXT_SMALL, target)?;
+            draw_line(&mut flow, &Self::count(value), TEXT_SMALL, target)?;
+        }
+
+        draw_line(&mut flow, "TYPING", TEXT_SMALL, target)?;
+        let secs = typing.session_typing_secs;
+        let mut text: String<8> = String::new();
+        write!(text, "{:>3}:{:02}", secs / 3600, secs / 60 % 60).unwrap();
+        draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        // Share of the key presses per layer
+        for (layer, percent) in typing.layer_percent.iter().enumerate() {
+            if layer >= LAYER_NAMES.len() {
+                break;
+            }
+            let name = layer_name(layer as u8);
+            text.clear();
+            write!(text, "{:<4}{:>3}%", &name[..name.len().min(4)], percent).unwrap();
+            draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Trackball mode and CPI, sensor status and image quality registers. The registers are used to
+/// tune lens height and bearings.
+pub struct PointingPage;
+
+impl Widget for PointingPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+        let title = match state.sensor_status {
+            None => "SENSOR",
+            Some(SensorStatus::Ok) => "SENS OK",
+            Some(_) => "SENS ERR",
+        };
+        draw_line(&mut flow, title, TEXT_SMALL, target)?;
+
+        let pointing = state.pointing;
+        draw_line(&mut flow, pointing.mode.name(), TEXT_SMALL, target)?;
+        let mut text: String<8> = String::new();
+        if pointing.cpi > 0 {
+            write!(text, "CPI{:>5}", pointing.cpi).unwrap();
+        } else {
+            write!(text, "CPI    ?").unwrap();
+        }
+        draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        let diag = state.sensor_diag;
+        let lines: [(&str, u16); 5] = [
+            ("SQ", diag.squal as u16),
+            ("SH", diag.shutter),
+            ("MX", diag.max_raw as u16),
+            ("MN", diag.min_raw as u16),
+            ("RS", diag.raw_data_sum as u16),
+        ];
+        for (label, value) in lines {
+            let mut text: String<8> = String::new();
+            write!(text, "{}:{:>5}", label, value).unwrap();
+            draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        }
+
+        // SQUAL bar, the PMW3360 reports up to about 0x80 features on a good surface
+        flow.skip(LINE_HEIGHT_SMALL);
+        let Some(area) = flow.place_area(6) else {
+            return Ok(());
+        };
+        let filled = (diag.squal as u32).min(0x80) * area.size.width / 0x80;
+        area.into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(target)?;
+        Rectangle::new(area.top_left, Size::new(filled, area.size.height))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)
+    }
+}
+
+/// Legends of the current layer on this half, as in keyboard.toml. Changes made with Vial
+/// are not shown.
+pub struct KeymapPage;
+
+impl KeymapPage {
+    /// Keys per line, a line of the small font fits three legends
+    const KEYS_PER_LINE: usize = 3;
+}
+
+impl Widget for KeymapPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+        draw_line(&mut flow, layer_name(state.layer), TEXT_NORM, target)?;
+
+        let Some(keys) = LAYER_KEYS.get(state.layer as usize) else {
+            return Ok(());
+        };
+        let legend_width = FONT_SMALL.character_size.width as i32 * 3;
+        for row in keys.chunks(KEYMAP_COLS) {
+            // Skip rows without any key on this layer, like the thumb rows
+            if row.iter().all(|legend| legend.is_empty()) {
+                continue;
+            }
+            for line in row.chunks(Self::KEYS_PER_LINE) {
+                let Some(line_position) = flow.place(LINE_HEIGHT_SMALL) else {
+                    return Ok(());
+                };
+                for (i, legend) in line.iter().enumerate() {
+                    let position = line_position + Point::new(i as i32 * legend_width, 0);
+                    draw_text(legend, position, TEXT_SMALL, target)?;
+                }
+            }
+            // Gap between the matrix rows
+            flow.skip(2);
+        }
+        Ok(())
+    }
+}
+
+/// Display settings and how to change them, the split link and the USB connection
+pub struct SettingsPage;
+
+impl SettingsPage {
+    /// Time a page is shown before the carousel switches to the next one
+    pub const CAROUSEL_INTERVAL: Duration = Duration::from_secs(5);
+}
+
+impl Widget for SettingsPage {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut flow = Flow::new(target.bounding_box().size);
+        draw_line(&mut flow, "SETUP", TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        draw_line(&mut flow, "CAROUSEL", TEXT_SMALL, target)?;
+        let mut text: String<8> = String::new();
+        if state.carousel {
+            write!(text, "ON {:>3}S", Self::CAROUSEL_INTERVAL.as_secs()).unwrap();
+        } else {
+            write!(text, "OFF").unwrap();
+        }
+        draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        draw_line(&mut flow, "CONTRAST", TEXT_SMALL, target)?;
+        text.clear();
+        write!(text, "{:>3}%", state.contrast as u16 * 100 / 0xff).unwrap();
+        draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        let link_text = match state.link {
+            None => "LINK   ?",
+            Some(link) if link.connected => "LINK  OK",
+            Some(_) => "LINK OFF",
+        };
+        draw_line(&mut flow, link_text, TEXT_SMALL, target)?;
+        let link = state.link.unwrap_or_default();
+        for (label, errors) in [("CRC", link.crc_errors), ("ERR", link.framing_errors)] {
+            text.clear();
+            write!(text, "{}{:>5}", label, errors.min(99999)).unwrap();
+            draw_line(&mut flow, &text, TEXT_SMALL, target)?;
+        }
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        let usb_text = match state.usb {
+            None => "USB    ?",
+            Some(UsbState::Detached) => "USB  OFF",
+            Some(UsbState::Powered) => "USB  PWR",
+            Some(UsbState::Configured) => "USB   OK",
+            Some(UsbState::Suspended) => "USB SUSP",
+            Some(UsbState::VialUnlocked) => "USB VIAL",
+        };
+        draw_line(&mut flow, usb_text, TEXT_SMALL, target)?;
+        flow.skip(LINE_HEIGHT_SMALL);
+
+        draw_line(&mut flow, "OLED", TEXT_SMALL, target)?;
+        text.clear();
+        write!(text, "ERR{:>5}", state.display_errors.min(99999)).unwrap();
+        draw_line(&mut flow, &text, TEXT_SMALL, target)
+    }
+}
+
+/// The notification, an inverted box over the middle of the page
+pub struct Toast;
+
+impl Toast {
+    const CHARS_PER_LINE: usize = 8;
+    const PADDING: i32 = 2;
+
+    /// Lines of `text`, broken at the last space that fits or in the middle of too long words
+    fn lines(mut text: &str) -> impl Iterator<Item = &str> {
+        core::iter::from_fn(move || {
+            text = text.trim_start();
+            if text.is_empty() {
+                return None;
+            }
+            // Counted in characters, the slices below must end on a character boundary
+            let end = match text.char_indices().nth(Self::CHARS_PER_LINE) {
+                None => text.len(),
+                Some((limit, c)) => text[..limit + c.len_utf8()].rfind(' ').unwrap_or(limit),
+            };
+            let (line, rest) = text.split_at(end);
+            text = rest;
+            Some(line.trim_end())
+        })
+    }
+}
+
+impl Widget for Toast {
+    fn draw<D>(&mut self, state: &DisplayState, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let Some(notification) = &state.notification else {
+            return Ok(());
+        };
+        let text = notification.text();
+        let lines = Self::lines(text).count().max(1);
+        let size = target.bounding_box().size;
+        let height = lines as i32 * LINE_HEIGHT_SMALL + 2 * Self::PADDING;
+        let top = (size.height as i32 - height) / 2;
+
+        Rectangle::new(Point::new(0, top), Size::new(size.width, height as u32))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)?;
+        let mut y = top + Self::PADDING;
+        for line in Self::lines(text) {
+            draw_text(line, Point::new(0, y), TEXT_SMALL_INV, target)?;
+            y += LINE_HEIGHT_SMALL;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toast_lines(text: &str) -> Vec<&str> {
+        Toast::lines(text).collect()
+    }
+
+    #[test]
+    fn breaks_toast_lines_at_spaces() {
+        assert_eq!(toast_lines("CPI 800"), ["CPI 800"]);
+        assert_eq!(toast_lines("JIGGLE ON"), ["JIGGLE", "ON"]);
+        assert_eq!(toast_lines("BALL  ERROR"), ["BALL", "ERROR"]);
+        assert_eq!(toast_lines("12345678 9"), ["12345678", "9"]);
+        assert!(toast_lines("  ").is_empty());
+    }
+
+    #[test]
+    fn splits_long_toast_words() {
+        assert_eq!(toast_lines("SENSORFAILED"), ["SENSORFA", "ILED"]);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::displaycommands::{DisplayCommand, DisplayCommandEvent};
+use crate::flashstore::{FlashStore, RECORD_DATA_LEN};
+use defmt::{info, warn};
+use embassy_time::{Duration, Instant};
+use embedded_storage_async::nor_flash::NorFlash;
+use rmk::event::publish_event;
+use rmk_macro::{event, processor};
+
+/// Kind of the display settings in the [`FlashStore`]
+const DISPLAY_SETTINGS_RECORD: u8 = 0;
+
+/// Contrast change of a key press
+const CONTRAST_STEP: u8 = 0x10;
+
+/// Display settings chosen by keys on the central and applied by the display on the peripheral
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct DisplaySettings {
+    /// OLED contrast while the display is not dimmed
+    pub contrast: u8,
+    pub display_on: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            // Contrast of ssd1306's `Brightness::NORMAL`
+            contrast: 0x5f,
+            display_on: true,
+        }
+    }
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct DisplaySettingsEvent(pub DisplaySettings);
+
+/// Applies the contrast and display on/off commands to the [`DisplaySettings`], publishes them
+/// and keeps them in flash. Runs on the central, the settings reach the display over the split
+/// link.
+#[processor(subscribe = [DisplayCommandEvent], poll_interval = 1000)]
+pub struct DisplaySettingsController<F: NorFlash> {
+    settings: DisplaySettings,
+    store: FlashStore<F>,
+    loaded: bool,
+    /// Time of the last change not saved yet
+    changed: Option<Instant>,
+    /// Time the settings were last published
+    published: Instant,
+}
+
+impl<F: NorFlash> DisplaySettingsController<F> {
+    /// Delay before a change is saved, so a row of contrast key presses is one flash write
+    const SAVE_DELAY: Duration = Duration::from_secs(2);
+    /// The settings are published again from time to time, the peripheral may boot later
+    const PUBLISH_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new(store: FlashStore<F>) -> Self {
+        Self {
+            settings: DisplaySettings::default(),
+            store,
+            loaded: false,
+            changed: None,
+            published: Instant::now(),
+        }
+    }
+
+    fn publish(&mut self) {
+        publish_event(DisplaySettingsEvent(self.settings));
+        self.published = Instant::now();
+    }
+
+    async fn on_display_command_event(&mut self, event: DisplayCommandEvent) {
+        let settings = &mut self.settings;
+        match event.0 {
+            DisplayCommand::ContrastUp => {
+                settings.contrast = settings.contrast.saturating_add(CONTRAST_STEP)
+            }
+            DisplayCommand::ContrastDown => {
+                settings.contrast = settings.contrast.saturating_sub(CONTRAST_STEP)
+            }
+            DisplayCommand::ToggleDisplay => settings.display_on = !settings.display_on,
+            _ => return,
+        }
+        info!("Display settings {}", self.settings);
+        self.changed = Some(Instant::now());
+        self.publish();
+    }
+
+    async fn load(&mut self) {
+        match self.store.load(DISPLAY_SETTINGS_RECORD).await {
+            Ok(Some(data)) => {
+                self.settings = DisplaySettings {
+                    contrast: data[0],
+                    display_on: data[1] != 0,
+                };
+                info!("Loaded display settings {}", self.settings);
+            }
+            Ok(None) => {}
+            Err(_) => warn!("Failed to load display settings"),
+        }
+    }
+
+    async fn save(&mut self) {
+        let mut data = [0u8; RECORD_DATA_LEN];
+        data[0] = self.settings.contrast;
+        data[1] = self.settings.display_on as u8;
+        if self
+            .store
+            .save(DISPLAY_SETTINGS_RECORD, &data)
+            .await
+            .is_err()
+        {
+            warn!("Failed to save display settings");
+        }
+    }
+
+    pub async fn poll(&mut self) {
+        if !self.loaded {
+            self.load().await;
+            self.loaded = true;
+            self.publish();
+        }
+
+        if self
+            .changed
+            .is_some_and(|changed| changed.elapsed() >= Self::SAVE_DELAY)
+        {
+            self.changed = None;
+            self.save().await;
+        }
+
+        if self.published.elapsed() >= Self::PUBLISH_INTERVAL {
+            self.publish();
+        }
+    }
+}
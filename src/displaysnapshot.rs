@@ -0,0 +1,94 @@
+//! Snapshot tests of the display pages.
+//!
+//! Every snapshot renders a page into a [`Framebuffer`] and compares it with the PBM image of
+//! the same name in `snapshots/`. After an intended change of a page, run the tests with
+//! `UPDATE_SNAPSHOTS=1` to write the rendered images instead and review them.
+use crate::displaypages::{DisplayState, PageId, Pages};
+use crate::framebuffer::Framebuffer;
+use crate::modifierstate::Modifiers;
+use crate::notification::{Notification, NOTIFICATION_TTL};
+use crate::usbstate::UsbState;
+
+/// Snapshots are of the default display, a 128x32 SSD1306 rotated by 90 degrees
+type DisplayFramebuffer = Framebuffer<32, 128>;
+
+const CAPS_LOCK: u8 = 1 << 1;
+
+/// Renders `page` for a fresh [`DisplayState`] changed by `setup` and compares it with the
+/// snapshot `name`
+fn check_snapshot(name: &str, page: PageId, setup: impl FnOnce(&mut DisplayState)) {
+    let mut state = DisplayState::new();
+    setup(&mut state);
+    let mut framebuffer = DisplayFramebuffer::new();
+    // Fresh pages, so animations start at their first frame
+    let Ok(()) = Pages::new().draw(page, &state, &mut framebuffer);
+
+    let path = format!("{}/snapshots/{}.pbm", env!("CARGO_MANIFEST_DIR"), name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, framebuffer.pbm()).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Cannot read snapshot {}: {}", path, e));
+    if let Err(mismatch) = framebuffer.compare_pbm(&expected) {
+        panic!(
+            "Snapshot {} differs: {:?}, rendered:\n{}",
+            name,
+            mismatch,
+            framebuffer.pbm()
+        );
+    }
+}
+
+#[test]
+fn status_caps_lock() {
+    check_snapshot("status_caps_lock", PageId::Status, |state| {
+        state.indicators = CAPS_LOCK.into()
+    });
+}
+
+#[test]
+fn status_layer_2() {
+    check_snapshot("status_layer_2", PageId::Status, |state| state.layer = 2);
+}
+
+#[test]
+fn status_jiggle() {
+    check_snapshot("status_jiggle", PageId::Status, |state| {
+        state.jiggle_active = true
+    });
+}
+
+#[test]
+fn status_wpm_120() {
+    check_snapshot("status_wpm_120", PageId::Status, |state| {
+        state.wpm = 120;
+        state.wpm_peak = 120;
+    });
+}
+
+#[test]
+fn status_modifiers() {
+    check_snapshot("status_modifiers", PageId::Status, |state| {
+        state.modifiers = Modifiers {
+            ctrl: true,
+            shift: true,
+            tap_hold: true,
+            ..Default::default()
+        }
+    });
+}
+
+#[test]
+fn status_notification() {
+    check_snapshot("status_notification", PageId::Status, |state| {
+        state.notification = Some(Notification::new("JIGGLE ON", NOTIFICATION_TTL))
+    });
+}
+
+#[test]
+fn status_vial_unlocked() {
+    check_snapshot("status_vial_unlocked", PageId::Status, |state| {
+        state.usb = Some(UsbState::VialUnlocked)
+    });
+}
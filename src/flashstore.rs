@@ -0,0 +1,375 @@
+//! Small records kept in flash on the central, next to rmk's own storage.
+//!
+//! A store owns two banks of at least a sector each. Records are appended to the active bank,
+//! the last valid record of a kind wins. When the bank is full, the last record of every kind
+//! is copied into the other bank and the full one is erased. The first slot of a bank holds a
+//! header with its generation, written after the copies: until then the full bank stays the
+//! active one, so a power loss while compacting loses nothing. A bank without header counts as
+//! generation 0, as written before the store had two banks.
+use crc::{Crc, CRC_8_SMBUS};
+use defmt::{info, warn};
+use embedded_storage_async::nor_flash::NorFlash;
+
+const RECORD_LEN: usize = 16;
+const RECORD_MAGIC: u8 = 0x5a;
+/// First byte of a slot that was never written since the last erase
+const ERASED: u8 = 0xff;
+/// Kind of the header record in the first slot of a bank, the data starts with the generation
+const HEADER_KIND: u8 = 0xfe;
+
+/// Payload of a record, after magic and kind, before the CRC
+pub const RECORD_DATA_LEN: usize = RECORD_LEN - 3;
+/// Number of record kinds kept over a compaction, kinds are `0..MAX_KINDS`
+pub const MAX_KINDS: u8 = 32;
+
+const RECORD_CRC: Crc<u8> = Crc::<u8>::new(&CRC_8_SMBUS);
+
+/// Where the records currently go
+#[derive(Clone, Copy)]
+struct Active {
+    bank: usize,
+    generation: u32,
+    /// First record slot, after the header if there is one
+    first: u32,
+    /// First free slot
+    next: u32,
+}
+
+pub struct FlashStore<F> {
+    banks: [F; 2],
+    /// `None` until the banks were scanned
+    active: Option<Active>,
+}
+
+impl<F: NorFlash> FlashStore<F> {
+    /// A store in two banks of the same size
+    pub fn new(bank_a: F, bank_b: F) -> Self {
+        assert!(
+            RECORD_LEN.is_multiple_of(F::WRITE_SIZE)
+                && F::ERASE_SIZE.is_multiple_of(RECORD_LEN)
+                && bank_a.capacity() == bank_b.capacity()
+                && bank_a.capacity() / RECORD_LEN > MAX_KINDS as usize
+        );
+        Self {
+            banks: [bank_a, bank_b],
+            active: None,
+        }
+    }
+
+    fn slots(&self) -> u32 {
+        (self.banks[0].capacity() / RECORD_LEN) as u32
+    }
+
+    /// Reads the record at `slot` of `bank`, `None` if the slot is erased
+    async fn read_slot(
+        &mut self,
+        bank: usize,
+        slot: u32,
+    ) -> Result<Option<[u8; RECORD_LEN]>, F::Error> {
+        let mut record = [0u8; RECORD_LEN];
+        self.banks[bank]
+            .read(slot * RECORD_LEN as u32, &mut record)
+            .await?;
+        Ok((record[0] != ERASED).then_some(record))
+    }
+
+    /// Kind and data of a record, `None` for a torn write
+    fn parse(record: &[u8; RECORD_LEN]) -> Option<(u8, [u8; RECORD_DATA_LEN])> {
+        let (content, crc) = record.split_at(RECORD_LEN - 1);
+        if content[0] != RECORD_MAGIC || RECORD_CRC.checksum(content) != crc[0] {
+            return None;
+        }
+        Some((content[1], content[2..].try_into().unwrap()))
+    }
+
+    /// Generation and first record slot of `bank`, `None` if it is empty
+    async fn bank_generation(&mut self, bank: usize) -> Result<Option<(u32, u32)>, F::Error> {
+        let Some(record) = self.read_slot(bank, 0).await? else {
+            return Ok(None);
+        };
+        Ok(Some(match Self::parse(&record) {
+            Some((HEADER_KIND, data)) => (u32::from_le_bytes(data[..4].try_into().unwrap()), 1),
+            _ => (0, 0),
+        }))
+    }
+
+    /// The active bank, scans the banks once. The newer generation wins, the first bank if both
+    /// are the same.
+    async fn active(&mut self) -> Result<Active, F::Error> {
+        if let Some(active) = self.active {
+            return Ok(active);
+        }
+        let (bank, (generation, first)) = match (
+            self.bank_generation(0).await?,
+            self.bank_generation(1).await?,
+        ) {
+            (Some(a), Some(b)) if b.0 > a.0 => (1, b),
+            (None, Some(b)) => (1, b),
+            (Some(a), _) => (0, a),
+            (None, None) => (0, (0, 0)),
+        };
+        let mut next = self.slots();
+        for slot in first..self.slots() {
+            if self.read_slot(bank, slot).await?.is_none() {
+                next = slot;
+                break;
+            }
+        }
+        let active = Active {
+            bank,
+            generation,
+            first,
+            next,
+        };
+        self.active = Some(active);
+        Ok(active)
+    }
+
+    /// Last valid record of `kind`
+    pub async fn load(&mut self, kind: u8) -> Result<Option<[u8; RECORD_DATA_LEN]>, F::Error> {
+        let active = self.active().await?;
+        let mut data = None;
+        for slot in active.first..active.next {
+            let Some(record) = self.read_slot(active.bank, slot).await? else {
+                break;
+            };
+            match Self::parse(&record) {
+                Some((record_kind, record_data)) if record_kind == kind => data = Some(record_data),
+                Some(_) => {}
+                // Torn write, e.g. power lost while saving
+                None => warn!("Invalid flash record in slot {}", slot),
+            }
+        }
+        Ok(data)
+    }
+
+    pub async fn save(&mut self, kind: u8, data: &[u8; RECORD_DATA_LEN]) -> Result<(), F::Error> {
+        assert!(kind < MAX_KINDS);
+        let mut active = self.active().await?;
+        if active.next >= self.slots() {
+            active = self.compact().await?;
+        }
+        self.write_record(active.bank, active.next, kind, data)
+            .await?;
+        self.active = Some(Active {
+            next: active.next + 1,
+            ..active
+        });
+        Ok(())
+    }
+
+    async fn write_record(
+        &mut self,
+        bank: usize,
+        slot: u32,
+        kind: u8,
+        data: &[u8; RECORD_DATA_LEN],
+    ) -> Result<(), F::Error> {
+        let mut record = [0u8; RECORD_LEN];
+        record[0] = RECORD_MAGIC;
+        record[1] = kind;
+        record[2..RECORD_LEN - 1].copy_from_slice(data);
+        record[RECORD_LEN - 1] = RECORD_CRC.checksum(&record[..RECORD_LEN - 1]);
+        self.banks[bank]
+            .write(slot * RECORD_LEN as u32, &record)
+            .await
+    }
+
+    /// Copies the last record of every kind into the other bank, makes it the active one and
+    /// erases the full bank
+    async fn compact(&mut self) -> Result<Active, F::Error> {
+        let full = self.active().await?;
+        let spare = 1 - full.bank;
+        info!("Flash records full, compacting into bank {}", spare);
+        let mut latest = [None; MAX_KINDS as usize];
+        for (kind, data) in latest.iter_mut().enumerate() {
+            *data = self.load(kind as u8).await?;
+        }
+
+        // Left over from a compaction that didn't finish
+        let capacity = self.banks[spare].capacity() as u32;
+        self.banks[spare].erase(0, capacity).await?;
+        let mut next = 1;
+        for (kind, data) in latest.iter().enumerate() {
+            if let Some(data) = data {
+                self.write_record(spare, next, kind as u8, data).await?;
+                next += 1;
+            }
+        }
+        let generation = full.generation + 1;
+        let mut header = [0u8; RECORD_DATA_LEN];
+        header[..4].copy_from_slice(&generation.to_le_bytes());
+        self.write_record(spare, 0, HEADER_KIND, &header).await?;
+
+        let active = Active {
+            bank: spare,
+            generation,
+            first: 1,
+            next,
+        };
+        self.active = Some(active);
+        self.banks[full.bank].erase(0, capacity).await?;
+        Ok(active)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embassy_futures::block_on;
+    use embedded_storage_async::nor_flash::{ErrorType, ReadNorFlash};
+
+    /// Room for every kind and the header
+    const SLOTS: u8 = 40;
+    const BANK_LEN: usize = SLOTS as usize * RECORD_LEN;
+
+    /// A bank in RAM that, like NOR flash, only clears bits when written
+    struct RamFlash {
+        data: [u8; BANK_LEN],
+    }
+
+    impl RamFlash {
+        fn new() -> Self {
+            Self {
+                data: [ERASED; BANK_LEN],
+            }
+        }
+    }
+
+    impl ErrorType for RamFlash {
+        type Error = Infallible;
+    }
+
+    impl ReadNorFlash for RamFlash {
+        const READ_SIZE: usize = 1;
+
+        async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Infallible> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.data[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            BANK_LEN
+        }
+    }
+
+    impl NorFlash for RamFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = BANK_LEN;
+
+        async fn erase(&mut self, from: u32, to: u32) -> Result<(), Infallible> {
+            self.data[from as usize..to as usize].fill(ERASED);
+            Ok(())
+        }
+
+        async fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Infallible> {
+            let offset = offset as usize;
+            for (cell, byte) in self.data[offset..].iter_mut().zip(bytes) {
+                *cell &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    fn data(value: u8) -> [u8; RECORD_DATA_LEN] {
+        [value; RECORD_DATA_LEN]
+    }
+
+    /// A store reopened on the banks of `store`, as after a reboot
+    fn reopen(store: FlashStore<RamFlash>) -> FlashStore<RamFlash> {
+        let [a, b] = store.banks;
+        FlashStore::new(a, b)
+    }
+
+    #[test]
+    fn loads_the_last_record_of_a_kind() {
+        block_on(async {
+            let mut store = FlashStore::new(RamFlash::new(), RamFlash::new());
+            assert_eq!(store.load(1).await, Ok(None));
+            store.save(1, &data(1)).await.unwrap();
+            store.save(2, &data(2)).await.unwrap();
+            store.save(1, &data(3)).await.unwrap();
+
+            let mut store = reopen(store);
+            assert_eq!(store.load(1).await, Ok(Some(data(3))));
+            assert_eq!(store.load(2).await, Ok(Some(data(2))));
+        });
+    }
+
+    #[test]
+    fn compacts_into_the_other_bank() {
+        block_on(async {
+            let mut store = FlashStore::new(RamFlash::new(), RamFlash::new());
+            // Two banks full and more
+            for i in 0..3 * SLOTS {
+                store.save(i % 3, &data(i)).await.unwrap();
+            }
+
+            let mut store = reopen(store);
+            assert_eq!(store.load(0).await, Ok(Some(data(3 * SLOTS - 3))));
+            assert_eq!(store.load(1).await, Ok(Some(data(3 * SLOTS - 2))));
+            assert_eq!(store.load(2).await, Ok(Some(data(3 * SLOTS - 1))));
+            assert!(store.active.unwrap().generation >= 2);
+        });
+    }
+
+    #[test]
+    fn keeps_the_full_bank_until_the_copy_is_complete() {
+        block_on(async {
+            let mut store = FlashStore::new(RamFlash::new(), RamFlash::new());
+            for i in 0..SLOTS {
+                store.save(i % 2, &data(i)).await.unwrap();
+            }
+            // Power lost while copying: records in the other bank, but no header yet
+            store.write_record(1, 1, 0, &data(0xaa)).await.unwrap();
+
+            let mut store = reopen(store);
+            assert_eq!(store.load(0).await, Ok(Some(data(SLOTS - 2))));
+            assert_eq!(store.load(1).await, Ok(Some(data(SLOTS - 1))));
+
+            // The next compaction starts over in the other bank
+            store.save(0, &data(0xbb)).await.unwrap();
+            let mut store = reopen(store);
+            assert_eq!(store.active.map(|active| active.bank), None);
+            assert_eq!(store.load(0).await, Ok(Some(data(0xbb))));
+            assert_eq!(store.load(1).await, Ok(Some(data(SLOTS - 1))));
+            assert_eq!(store.active.unwrap().bank, 1);
+        });
+    }
+
+    #[test]
+    fn reads_a_bank_without_header() {
+        block_on(async {
+            // Written with a single bank, records from the first slot on
+            let mut store = FlashStore::new(RamFlash::new(), RamFlash::new());
+            store.write_record(0, 0, 4, &data(1)).await.unwrap();
+            store.write_record(0, 1, 4, &data(2)).await.unwrap();
+
+            let mut store = reopen(store);
+            assert_eq!(store.load(4).await, Ok(Some(data(2))));
+            store.save(4, &data(3)).await.unwrap();
+            let mut store = reopen(store);
+            assert_eq!(store.load(4).await, Ok(Some(data(3))));
+        });
+    }
+
+    #[test]
+    fn skips_torn_records() {
+        block_on(async {
+            let mut store = FlashStore::new(RamFlash::new(), RamFlash::new());
+            store.save(1, &data(1)).await.unwrap();
+            // Power lost while writing the next record
+            let mut torn = [0u8; RECORD_LEN];
+            torn[..4].copy_from_slice(&[RECORD_MAGIC, 1, 2, 2]);
+            store.banks[0]
+                .write(RECORD_LEN as u32, &torn)
+                .await
+                .unwrap();
+
+            let mut store = reopen(store);
+            assert_eq!(store.load(1).await, Ok(Some(data(1))));
+        });
+    }
+}
@@ -0,0 +1,108 @@
+use core::convert::Infallible;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+
+/// Monochrome in-memory `DrawTarget` of `W` x `H` pixels, the display pages render into it the
+/// same way as into the display. Used by the snapshot tests.
+pub struct Framebuffer<const W: usize, const H: usize> {
+    pixels: [[bool; W]; H],
+}
+
+impl<const W: usize, const H: usize> Default for Framebuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Framebuffer<W, H> {
+    pub const fn new() -> Self {
+        Self {
+            pixels: [[false; W]; H],
+        }
+    }
+
+    /// Header of a plain PBM (`P1`) image of the framebuffer
+    pub fn pbm_header(&self) -> String {
+        format!("P1\n{} {}\n", W, H)
+    }
+
+    /// Row `y` of the framebuffer as in a plain PBM image, `1` is black (a lit pixel of the OLED)
+    pub fn pbm_row(&self, y: usize) -> String {
+        self.pixels[y]
+            .iter()
+            .map(|on| if *on { '1' } else { '0' })
+            .collect()
+    }
+
+    /// The framebuffer as plain PBM image, one row per line
+    pub fn pbm(&self) -> String {
+        let mut pbm = self.pbm_header();
+        for y in 0..H {
+            pbm += &self.pbm_row(y);
+            pbm.push('\n');
+        }
+        pbm
+    }
+
+    /// Compares the framebuffer with a plain PBM image written by [`Framebuffer::pbm`]
+    pub fn compare_pbm(&self, pbm: &str) -> Result<(), PbmMismatch> {
+        let raster = pbm
+            .strip_prefix(&self.pbm_header())
+            .ok_or(PbmMismatch::Header)?;
+
+        let mut rows = 0;
+        for (y, expected) in raster.lines().enumerate() {
+            if y >= H {
+                return Err(PbmMismatch::Header);
+            }
+            if self.pbm_row(y) != expected {
+                return Err(PbmMismatch::Row(y));
+            }
+            rows += 1;
+        }
+        if rows != H {
+            return Err(PbmMismatch::Header);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PbmMismatch {
+    /// Not a plain PBM image of the framebuffer's size
+    Header,
+    /// First row that differs
+    Row(usize),
+}
+
+impl<const W: usize, const H: usize> OriginDimensions for Framebuffer<W, H> {
+    fn size(&self) -> Size {
+        Size::new(W as u32, H as u32)
+    }
+}
+
+impl<const W: usize, const H: usize> DrawTarget for Framebuffer<W, H> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            // Drawing outside of the display is clipped, as on the display
+            let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                continue;
+            };
+            if x < W && y < H {
+                self.pixels[y][x] = color.is_on();
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels = [[color.is_on(); W]; H];
+        Ok(())
+    }
+}
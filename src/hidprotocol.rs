@@ -0,0 +1,265 @@
+//! Protocol of the host's queries over Vial's raw HID interface, answered by
+//! [`crate::hidquery`] on the central.
+//!
+//! VIA reserves channel 0 of its custom value commands for the keyboard itself, rmk hands
+//! those packets to us and sends our answer back. A query is a 32 byte report:
+//!
+//! `id_custom_get_value (0x08) | channel (0) | value id | arguments`
+//!
+//! The answer repeats the first three bytes followed by the value, an unknown value id is
+//! answered with `id_unhandled (0xff)` in the first byte as VIA does for unknown commands.
+//!
+//! [`VALUE_SENSOR_DIAG`]: `status | SQUAL | Raw_Data_Sum | max raw | min raw | shutter (u16) |
+//! sample (u16)`, all little endian. The sample number counts the readouts of the sensor, a
+//! host polling faster than [`crate::sensormonitor`] samples sees each one once by skipping
+//! repeated numbers.
+//!
+//! The typing statistics of [`crate::typingstats`], all `u32` little endian:
+//!
+//! - [`VALUE_TYPING_TOTALS`]: `key presses | typing seconds | rows | columns | layers`
+//! - [`VALUE_LAYER_PRESSES`]: argument `first layer`, answered with `first layer` followed by
+//!   the key presses on up to [`COUNTERS_PER_REPORT`] layers from there
+//! - [`VALUE_KEY_PRESSES`]: argument `first key`, answered with `first key` followed by the key
+//!   presses of up to [`COUNTERS_PER_REPORT`] keys from there. Keys are numbered row by row,
+//!   `row * columns + column`, reading all of them gives the heatmap.
+//!
+//! Counters past the last layer or key are left 0.
+use crate::sensorstate::{SensorDiag, SensorStatus};
+use crate::typingstate::TypingCounters;
+
+pub const REPORT_LEN: usize = 32;
+
+pub const ID_CUSTOM_GET_VALUE: u8 = 0x08;
+pub const ID_UNHANDLED: u8 = 0xff;
+pub const CHANNEL_CUSTOM: u8 = 0;
+/// Command, channel and value id
+pub const HEADER_LEN: usize = 3;
+
+/// Latest image quality readout of the sensor
+pub const VALUE_SENSOR_DIAG: u8 = 0x01;
+/// Key presses and typing time so far, and the size of the statistics
+pub const VALUE_TYPING_TOTALS: u8 = 0x02;
+/// Key presses per layer
+pub const VALUE_LAYER_PRESSES: u8 = 0x03;
+/// Key presses per key
+pub const VALUE_KEY_PRESSES: u8 = 0x04;
+
+/// Counters answered to a layer or key query, after the header and the first index
+pub const COUNTERS_PER_REPORT: usize = (REPORT_LEN - HEADER_LEN - 1) / 4;
+
+/// The values the host can query
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryValues {
+    /// `None` until the SROM check finished
+    pub sensor_status: Option<SensorStatus>,
+    pub sensor_diag: SensorDiag,
+    /// Readouts of `sensor_diag` so far, wrapping
+    pub diag_samples: u16,
+}
+
+/// Builds the answer to `request`
+pub fn respond<const ROW: usize, const COL: usize, const NUM_LAYER: usize>(
+    request: &[u8; REPORT_LEN],
+    values: &QueryValues,
+    counters: &TypingCounters<ROW, COL, NUM_LAYER>,
+) -> [u8; REPORT_LEN] {
+    let mut response = [0u8; REPORT_LEN];
+    response[..HEADER_LEN].copy_from_slice(&request[..HEADER_LEN]);
+    let data = &mut response[HEADER_LEN..];
+    match (request[0], request[1], request[2]) {
+        (ID_CUSTOM_GET_VALUE, CHANNEL_CUSTOM, VALUE_SENSOR_DIAG) => {
+            let diag = values.sensor_diag;
+            // No status yet reads as not answering, the sensor may still boot
+            let status = values.sensor_status.unwrap_or(SensorStatus::NoResponse);
+            let [shutter_lo, shutter_hi] = diag.shutter.to_le_bytes();
+            let [sample_lo, sample_hi] = values.diag_samples.to_le_bytes();
+            data[..9].copy_from_slice(&[
+                status as u8,
+                diag.squal,
+                diag.raw_data_sum,
+                diag.max_raw,
+                diag.min_raw,
+                shutter_lo,
+                shutter_hi,
+                sample_lo,
+                sample_hi,
+            ]);
+        }
+        (ID_CUSTOM_GET_VALUE, CHANNEL_CUSTOM, VALUE_TYPING_TOTALS) => {
+            data[..4].copy_from_slice(&counters.total_presses.to_le_bytes());
+            data[4..8].copy_from_slice(&counters.typing_secs().to_le_bytes());
+            data[8..11].copy_from_slice(&[ROW as u8, COL as u8, NUM_LAYER as u8]);
+        }
+        (ID_CUSTOM_GET_VALUE, CHANNEL_CUSTOM, VALUE_LAYER_PRESSES) => {
+            write_counters(data, request[HEADER_LEN], &counters.layer_presses);
+        }
+        (ID_CUSTOM_GET_VALUE, CHANNEL_CUSTOM, VALUE_KEY_PRESSES) => {
+            write_counters(
+                data,
+                request[HEADER_LEN],
+                counters.key_presses.as_flattened(),
+            );
+        }
+        _ => response[0] = ID_UNHANDLED,
+    }
+    response
+}
+
+/// Writes `first` and the counters from there into `data`
+fn write_counters(data: &mut [u8], first: u8, counters: &[u32]) {
+    data[0] = first;
+    let counters = counters
+        .iter()
+        .skip(first as usize)
+        .take(COUNTERS_PER_REPORT);
+    let (values, _) = data[1..].as_chunks_mut::<4>();
+    for (value, counter) in values.iter_mut().zip(counters) {
+        *value = counter.to_le_bytes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embassy_time::Duration;
+
+    type Counters = TypingCounters<2, 6, 3>;
+
+    fn request(value: u8, argument: u8) -> [u8; REPORT_LEN] {
+        let mut request = [0; REPORT_LEN];
+        request[..4].copy_from_slice(&[ID_CUSTOM_GET_VALUE, CHANNEL_CUSTOM, value, argument]);
+        request
+    }
+
+    fn counters() -> Counters {
+        let mut counters = Counters::new();
+        counters.total_presses = 0x0102_0304;
+        counters.typing_secs_before = 3600;
+        counters.session_typing = Duration::from_secs(90);
+        counters.layer_presses = [1000, 200, 30];
+        for (i, presses) in counters
+            .key_presses
+            .as_flattened_mut()
+            .iter_mut()
+            .enumerate()
+        {
+            *presses = i as u32 * 10;
+        }
+        counters
+    }
+
+    fn u32_at(response: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(response[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn answers_the_sensor_diagnostics() {
+        let values = QueryValues {
+            sensor_status: Some(SensorStatus::Ok),
+            sensor_diag: SensorDiag {
+                squal: 0x40,
+                raw_data_sum: 0x81,
+                max_raw: 0xf0,
+                min_raw: 0x02,
+                shutter: 0x1234,
+            },
+            diag_samples: 0x0506,
+        };
+        let response = respond(&request(VALUE_SENSOR_DIAG, 0), &values, &counters());
+        assert_eq!(
+            response[..12],
+            [0x08, 0, 0x01, 0, 0x40, 0x81, 0xf0, 0x02, 0x34, 0x12, 0x06, 0x05]
+        );
+        assert!(response[12..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn reports_a_booting_sensor_as_not_answering() {
+        let values = QueryValues::default();
+        let response = respond(&request(VALUE_SENSOR_DIAG, 0), &values, &counters());
+        assert_eq!(response[3], SensorStatus::NoResponse as u8);
+    }
+
+    #[test]
+    fn answers_the_typing_totals() {
+        let response = respond(
+            &request(VALUE_TYPING_TOTALS, 0),
+            &QueryValues::default(),
+            &counters(),
+        );
+        assert_eq!(response[..3], [0x08, 0, 0x02]);
+        assert_eq!(u32_at(&response, 3), 0x0102_0304);
+        assert_eq!(u32_at(&response, 7), 3690);
+        // Rows, columns and layers
+        assert_eq!(response[11..14], [2, 6, 3]);
+    }
+
+    #[test]
+    fn answers_the_layer_presses() {
+        let response = respond(
+            &request(VALUE_LAYER_PRESSES, 1),
+            &QueryValues::default(),
+            &counters(),
+        );
+        assert_eq!(response[..4], [0x08, 0, 0x03, 1]);
+        assert_eq!(u32_at(&response, 4), 200);
+        assert_eq!(u32_at(&response, 8), 30);
+        // Past the last layer
+        assert_eq!(u32_at(&response, 12), 0);
+    }
+
+    #[test]
+    fn pages_through_the_heatmap() {
+        let counters = counters();
+        let mut heatmap = Vec::new();
+        for first in (0..12).step_by(COUNTERS_PER_REPORT) {
+            let response = respond(
+                &request(VALUE_KEY_PRESSES, first as u8),
+                &QueryValues::default(),
+                &counters,
+            );
+            assert_eq!(response[..4], [0x08, 0, 0x04, first as u8]);
+            let keys = (12 - first).min(COUNTERS_PER_REPORT);
+            heatmap.extend((0..keys).map(|key| u32_at(&response, 4 + key * 4)));
+        }
+        assert_eq!(heatmap, counters.key_presses.as_flattened());
+    }
+
+    #[test]
+    fn leaves_counters_past_the_last_key_zero() {
+        let response = respond(
+            &request(VALUE_KEY_PRESSES, 10),
+            &QueryValues::default(),
+            &counters(),
+        );
+        assert_eq!(u32_at(&response, 4), 100);
+        assert_eq!(u32_at(&response, 8), 110);
+        assert!(response[12..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn rejects_unknown_queries() {
+        let values = QueryValues::default();
+        for mut request in [
+            request(0x05, 0),
+            request(0x00, 0),
+            // Other channels belong to the lighting of VIA
+            {
+                let mut request = request(VALUE_SENSOR_DIAG, 0);
+                request[1] = 1;
+                request
+            },
+            // Only get value is answered
+            {
+                let mut request = request(VALUE_SENSOR_DIAG, 0);
+                request[0] = 0x07;
+                request
+            },
+        ] {
+            let response = respond(&request, &values, &counters());
+            request[0] = ID_UNHANDLED;
+            assert_eq!(response[..HEADER_LEN], request[..HEADER_LEN]);
+            assert!(response[HEADER_LEN..].iter().all(|&byte| byte == 0));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Answers the host's queries over Vial's raw HID interface, see [`crate::hidprotocol`] for
+//! the protocol.
+//!
+//! rmk hands the custom value packets to us over `VIA_CUSTOM_REQUEST_CHANNEL` and sends our
+//! answer from `VIA_CUSTOM_RESPONSE_CHANNEL`. Only built with the `hid-query` feature, for an
+//! rmk that has these channels.
+use crate::hidprotocol::{respond, QueryValues, HEADER_LEN};
+use crate::sensorstate::{SensorDiagEvent, SensorStatusEvent};
+use crate::typingstate::TypingCounters;
+use core::cell::RefCell;
+use defmt::debug;
+use rmk::channel::{VIA_CUSTOM_REQUEST_CHANNEL, VIA_CUSTOM_RESPONSE_CHANNEL};
+use rmk_macro::processor;
+
+/// Keeps the values of [`QueryValues`] up to date and answers the queries rmk received
+#[processor(subscribe = [SensorStatusEvent, SensorDiagEvent], poll_interval = 10)]
+pub struct HidQuery<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize> {
+    values: QueryValues,
+    counters: &'a RefCell<TypingCounters<ROW, COL, NUM_LAYER>>,
+}
+
+impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize>
+    HidQuery<'a, ROW, COL, NUM_LAYER>
+{
+    pub fn new(counters: &'a RefCell<TypingCounters<ROW, COL, NUM_LAYER>>) -> Self {
+        assert!(ROW * COL <= u8::MAX as usize + 1 && NUM_LAYER <= u8::MAX as usize);
+        Self {
+            values: QueryValues::default(),
+            counters,
+        }
+    }
+
+    async fn on_sensor_status_event(&mut self, event: SensorStatusEvent) {
+        self.values.sensor_status = Some(event.0);
+    }
+
+    async fn on_sensor_diag_event(&mut self, event: SensorDiagEvent) {
+        self.values.sensor_diag = event.0;
+        self.values.diag_samples = self.values.diag_samples.wrapping_add(1);
+    }
+
+    pub async fn poll(&mut self) {
+        while let Ok(request) = VIA_CUSTOM_REQUEST_CHANNEL.try_receive() {
+            debug!("HID query {:#x}", request[..HEADER_LEN]);
+            // The counters must not stay borrowed while waiting for rmk to take the answer
+            let response = respond(&request, &self.values, &self.counters.borrow());
+            VIA_CUSTOM_RESPONSE_CHANNEL.send(response).await;
+        }
+    }
+}
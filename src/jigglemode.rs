@@ -1,3 +1,4 @@
+use crate::notification::{Notification, NotificationEvent, NOTIFICATION_TTL};
 use core::cell::RefCell;
 use core::sync::atomic::{AtomicBool, Ordering};
 use defmt::info;
@@ -62,6 +63,8 @@ impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, const NUM_E
                     info!("Jiggle was {}, storing {}", current, !current);
                     JIGGLE_ACTIVE.store(!current, Ordering::SeqCst);
                     publish_event(JiggleEvent(!current));
+                    let text = if current { "JIGGLE OFF" } else { "JIGGLE ON" };
+                    publish_event(NotificationEvent(Notification::new(text, NOTIFICATION_TTL)));
                 }
                 _ => {}
             }
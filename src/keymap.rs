@@ -30,6 +30,14 @@ const SC_RSHIFT: KeyAction = KeyAction::TapHold(
     MorseProfile::const_default(),
 );
 const USER0: KeyAction = KeyAction::Single(Action::User(0));
+const USER1: KeyAction = KeyAction::Single(Action::User(1));
+const USER2: KeyAction = KeyAction::Single(Action::User(2));
+const USER3: KeyAction = KeyAction::Single(Action::User(3));
+const USER4: KeyAction = KeyAction::Single(Action::User(4));
+const USER5: KeyAction = KeyAction::Single(Action::User(5));
+const USER6: KeyAction = KeyAction::Single(Action::User(6));
+const USER7: KeyAction = KeyAction::Single(Action::User(7));
+const USER8: KeyAction = KeyAction::Single(Action::User(8));
 #[rustfmt::skip]
 pub const fn get_default_keymap() -> [[[KeyAction; COL]; ROW]; NUM_LAYER] {
     [
@@ -43,14 +51,14 @@ pub const fn get_default_keymap() -> [[[KeyAction; COL]; ROW]; NUM_LAYER] {
         ]),
         layer!([
 [a!(No),      k!(F1),       k!(F2),      k!(F3),      k!(F4),     k!(F5),                        k!(F6),        k!(F7),       k!(F8),      k!(F9),      k!(F10),        k!(Delete)],
-[a!(No),      a!(No),       a!(No),      a!(No),      a!(No), shifted!(LeftBracket),    shifted!(RightBracket), k!(MouseBtn2), a!(No),   a!(No),       a!(No),        a!(No)],
+[USER1,       USER3,        USER2,       USER4,       USER8,  shifted!(LeftBracket),    shifted!(RightBracket), k!(MouseBtn2), a!(No),   a!(No),       a!(No),        a!(No)],
 [USER0,   a!(No),       a!(No),      mo!(2),      k!(Delete), shifted!(Kc9),           shifted!(Kc0), k!(Left),    k!(Up),      k!(Down),     k!(Right),    a!(No)],
 [k!(CapsLock), a!(No),      a!(No),     wm!(X, LCTRL), wm!(C, LCTRL), wm!(V, LCTRL),             a!(No),         k!(MouseBtn1), a!(No),      a!(No),       a!(No),        a!(No)],
 [a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No),                                                              a!(No), a!(No)],
 [a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No), a!(No),                                                              a!(No), a!(No)]
         ]),
         layer!([
-[a!(No),      a!(No),       a!(No),      a!(No),       a!(No),     a!(No),                        a!(No),       k!(NumLock), k!(KpSlash), k!(KpAsterisk), k!(KpMinus), k!(Calculator)],
+[a!(No),      USER6,        USER5,       USER7,        a!(No),     a!(No),                        a!(No),       k!(NumLock), k!(KpSlash), k!(KpAsterisk), k!(KpMinus), k!(Calculator)],
 [a!(No),      a!(No),       a!(No),      a!(No),       a!(No),     a!(No),                        a!(No),        k!(Kp7),    k!(Kp8),    k!(Kp9),    k!(KpPlus),  k!(AudioMute)],
 [a!(No),      a!(No),       a!(No),      a!(No),       a!(No),     a!(No),                        a!(No),        k!(Kp4),    k!(Kp5),    k!(Kp6),    a!(No),      k!(AudioVolUp)],
 [a!(No),      a!(No),       a!(No),      a!(No),       a!(No),     a!(No),                        k!(Kp0),       k!(Kp1),    k!(Kp2),    k!(Kp3),    k!(KpEqual), k!(AudioVolDown)],
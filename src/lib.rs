@@ -0,0 +1,54 @@
+//! The hardware independent parts of the firmware, built for the host by the tests. The
+//! `central` and `peripheral` binaries declare these modules themselves.
+#![cfg_attr(not(test), no_std)]
+
+pub mod animation;
+pub mod bitmap;
+#[cfg(feature = "pet-cat")]
+pub mod catsprites;
+pub mod displaycommands;
+pub mod displaylayout;
+pub mod displaypages;
+pub mod displaysettings;
+#[cfg(test)]
+mod displaysnapshot;
+pub mod flashstore;
+#[cfg(test)]
+mod framebuffer;
+pub mod hidprotocol;
+pub mod linkstate;
+pub mod modifierstate;
+#[cfg(test)]
+mod motionreplay;
+pub mod motiontrace;
+pub mod notification;
+pub mod oled;
+pub mod pointingstate;
+pub mod scrollmotion;
+pub mod sensorstate;
+pub mod splitframe;
+pub mod sprites;
+pub mod typingstate;
+pub mod usbstate;
+pub mod wpmhistory;
+
+/// The tests log nowhere, defmt only needs a logger to link
+#[cfg(test)]
+mod test_logger {
+    #[defmt::global_logger]
+    struct Logger;
+
+    unsafe impl defmt::Logger for Logger {
+        fn acquire() {}
+        unsafe fn flush() {}
+        unsafe fn release() {}
+        unsafe fn write(_bytes: &[u8]) {}
+    }
+
+    defmt::timestamp!("");
+
+    #[defmt::panic_handler]
+    fn panic() -> ! {
+        panic!("defmt panic")
+    }
+}
@@ -0,0 +1,18 @@
+use rmk_macro::event;
+
+/// Connection and error counters of the split link, as seen by the receiving half
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct LinkStatus {
+    /// A valid frame arrived recently, the other half sends at least a heartbeat every second
+    pub connected: bool,
+    /// Valid frames received since boot
+    pub frames: u32,
+    /// Frames dropped because of a CRC mismatch
+    pub crc_errors: u32,
+    /// Frames with an impossible length, unknown channel or unknown message
+    pub framing_errors: u32,
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct LinkStatusEvent(pub LinkStatus);
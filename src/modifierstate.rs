@@ -0,0 +1,40 @@
+use rmk_macro::event;
+
+/// Modifiers held on the keyboard, as shown on the display
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub gui: bool,
+    /// A tap-hold key is held long enough to act as its hold action, like `SC_LSHIFT`
+    pub tap_hold: bool,
+}
+
+impl Modifiers {
+    pub const fn from_bits(bits: u8) -> Self {
+        Self {
+            ctrl: bits & 0x01 != 0,
+            shift: bits & 0x02 != 0,
+            alt: bits & 0x04 != 0,
+            gui: bits & 0x08 != 0,
+            tap_hold: bits & 0x10 != 0,
+        }
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.ctrl as u8
+            | (self.shift as u8) << 1
+            | (self.alt as u8) << 2
+            | (self.gui as u8) << 3
+            | (self.tap_hold as u8) << 4
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self::from_bits(self.bits() | other.bits())
+    }
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct ModifiersEvent(pub Modifiers);
@@ -0,0 +1,154 @@
+//! Follows the modifiers held on the central for the display. rmk publishes neither its
+//! modifier state nor how tap-hold keys resolve, so both are derived from the key events and
+//! the keymap.
+use crate::modifierstate::{Modifiers, ModifiersEvent};
+use core::cell::RefCell;
+use defmt::debug;
+use embassy_time::{Duration, Instant};
+use rmk::event::publish_event;
+use rmk::event::{KeyPos, KeyboardEvent, KeyboardEventPos, LayerChangeEvent};
+use rmk::heapless::Vec;
+use rmk::keymap::KeyMap;
+use rmk::types::action::{Action, KeyAction};
+use rmk::types::keycode::{HidKeyCode, KeyCode};
+use rmk_macro::processor;
+
+/// Hold timeout of the default morse profile in central.rs, a tap-hold key held this long
+/// acts as its hold action
+const TAP_HOLD_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Keys held at once that are followed
+const MAX_HELD: usize = 10;
+
+struct HeldKey {
+    row: u8,
+    col: u8,
+    /// Modifiers of the key itself
+    modifiers: Modifiers,
+    /// Modifiers of the hold action of a tap-hold key, `None` for other keys
+    hold: Option<Modifiers>,
+    pressed: Instant,
+    /// The tap-hold key acts as its hold action
+    resolved: bool,
+}
+
+/// Modifiers of a modifier key action, none for other actions
+fn action_modifiers(action: Action) -> Modifiers {
+    let Action::Key(KeyCode::Hid(key)) = action else {
+        return Modifiers::default();
+    };
+    Modifiers {
+        ctrl: matches!(key, HidKeyCode::LCtrl | HidKeyCode::RCtrl),
+        shift: matches!(key, HidKeyCode::LShift | HidKeyCode::RShift),
+        alt: matches!(key, HidKeyCode::LAlt | HidKeyCode::RAlt),
+        gui: matches!(key, HidKeyCode::LGui | HidKeyCode::RGui),
+        tap_hold: false,
+    }
+}
+
+/// Publishes [`ModifiersEvent`]s for the display when the held modifiers change. A tap-hold
+/// key counts as held after `TAP_HOLD_TIMEOUT`, or once another key was pressed and released
+/// while it is down, as with the permissive hold of central.rs.
+#[processor(subscribe = [LayerChangeEvent, KeyboardEvent], poll_interval = 20)]
+pub struct ModifierTracker<
+    'a,
+    const ROW: usize,
+    const COL: usize,
+    const NUM_LAYER: usize,
+    const NUM_ENCODER: usize,
+> {
+    current_layer: u8,
+    keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>,
+    held: Vec<HeldKey, MAX_HELD>,
+    /// Last published modifiers
+    modifiers: Modifiers,
+}
+
+impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, const NUM_ENCODER: usize>
+    ModifierTracker<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>
+{
+    pub fn new(keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>) -> Self {
+        Self {
+            current_layer: 0,
+            keymap,
+            held: Vec::new(),
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
+        self.current_layer = event.layer;
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        let KeyboardEventPos::Key(KeyPos { row, col }) = event.pos else {
+            return;
+        };
+
+        if event.pressed {
+            // The action of the layer at the press stays in effect until the release
+            let action = self
+                .keymap
+                .borrow()
+                .get_action_at(event.pos, self.current_layer as usize);
+            let (modifiers, hold) = match action {
+                KeyAction::Single(action) => (action_modifiers(action), None),
+                KeyAction::TapHold(_, hold, _) => {
+                    (Modifiers::default(), Some(action_modifiers(hold)))
+                }
+                _ => (Modifiers::default(), None),
+            };
+            let key = HeldKey {
+                row,
+                col,
+                modifiers,
+                hold,
+                pressed: Instant::now(),
+                resolved: false,
+            };
+            // More keys held at once than followed, their modifiers are missed
+            let _ = self.held.push(key);
+        } else {
+            let Some(index) = self
+                .held
+                .iter()
+                .position(|key| (key.row, key.col) == (row, col))
+            else {
+                return;
+            };
+            let released = self.held.swap_remove(index);
+            // Permissive hold: a key tapped within a tap-hold key makes it a hold
+            for key in self.held.iter_mut() {
+                if key.hold.is_some() && key.pressed < released.pressed {
+                    key.resolved = true;
+                }
+            }
+        }
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let mut modifiers = Modifiers::default();
+        for key in self.held.iter() {
+            modifiers = modifiers.union(key.modifiers);
+            if let (Some(hold), true) = (key.hold, key.resolved) {
+                modifiers = modifiers.union(hold);
+                modifiers.tap_hold = true;
+            }
+        }
+        if modifiers != self.modifiers {
+            debug!("Modifiers {}", modifiers);
+            self.modifiers = modifiers;
+            publish_event(ModifiersEvent(modifiers));
+        }
+    }
+
+    pub async fn poll(&mut self) {
+        for key in self.held.iter_mut() {
+            if key.hold.is_some() && key.pressed.elapsed() >= TAP_HOLD_TIMEOUT {
+                key.resolved = true;
+            }
+        }
+        self.update();
+    }
+}
@@ -0,0 +1,225 @@
+//! Replay of the motion traces in `traces/` for the host tests.
+//!
+//! [`parse_line`] reads the samples back from a trace logged by the `motion-trace` feature (see
+//! `src/motiontrace.rs`) and [`replay`] sums them into the motion bursts the driver reads at a
+//! given interval. The tests feed the bursts through the scroll code of the central and check
+//! the wheel and pan reports, so the motion handling can be checked without hardware. Cursor
+//! motion is left to rmk's pointing processor and isn't replayed here.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MotionSample {
+    /// Time since the first sample
+    pub t_ms: u32,
+    pub dx: i16,
+    pub dy: i16,
+}
+
+/// A `motion` line of a trace file without valid time and deltas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidSample;
+
+/// Parses a line of a trace file. Sample lines look like `motion <ms> <dx> <dy> <squal>`,
+/// anything before `motion` (timestamps, log levels of probe-rs) is skipped and lines without
+/// it are no sample.
+pub fn parse_line(line: &str) -> Result<Option<MotionSample>, InvalidSample> {
+    let Some((_, sample)) = line.split_once("motion ") else {
+        return Ok(None);
+    };
+    let mut fields = sample.split_whitespace();
+    let mut field = || fields.next().ok_or(InvalidSample);
+    let t_ms = field()?.parse().map_err(|_| InvalidSample)?;
+    let dx = field()?.parse().map_err(|_| InvalidSample)?;
+    let dy = field()?.parse().map_err(|_| InvalidSample)?;
+    Ok(Some(MotionSample { t_ms, dx, dy }))
+}
+
+/// Sums the deltas of the samples up to `t_ms`, as one motion burst read at that time. Returns
+/// the number of samples used and the deltas.
+pub fn accumulate(samples: &[MotionSample], t_ms: u32) -> (usize, i16, i16) {
+    let due = samples.partition_point(|sample| sample.t_ms <= t_ms);
+    let (dx, dy) = samples[..due]
+        .iter()
+        .fold((0i16, 0i16), |(dx, dy), sample| {
+            (dx.saturating_add(sample.dx), dy.saturating_add(sample.dy))
+        });
+    (due, dx, dy)
+}
+
+/// The deltas of the bursts read every `interval_ms` while the trace plays, until the last
+/// sample was read
+pub fn replay(samples: &[MotionSample], interval_ms: u32) -> impl Iterator<Item = (i16, i16)> + '_ {
+    let mut rest = samples;
+    let mut t_ms = 0;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        t_ms += interval_ms;
+        let (used, dx, dy) = accumulate(rest, t_ms);
+        rest = &rest[used..];
+        Some((dx, dy))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motiontrace::{has_motion, MOTION_MOT};
+    use crate::scrollmotion::{take_motion, ScrollAccumulator};
+
+    const SQUARE: &str = include_str!("../traces/square.txt");
+
+    fn square() -> Vec<MotionSample> {
+        SQUARE
+            .lines()
+            .filter_map(|line| parse_line(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_samples_after_the_log_prefix() {
+        let samples = square();
+        assert_eq!(samples.len(), 12);
+        assert_eq!(
+            samples[0],
+            MotionSample {
+                t_ms: 8,
+                dx: 40,
+                dy: 0
+            }
+        );
+        assert_eq!(
+            samples[11],
+            MotionSample {
+                t_ms: 96,
+                dx: 0,
+                dy: -40
+            }
+        );
+    }
+
+    #[test]
+    fn skips_lines_without_samples() {
+        assert_eq!(parse_line("0.000412 INFO  Sensor SROM verified"), Ok(None));
+        assert_eq!(parse_line("# comment"), Ok(None));
+    }
+
+    #[test]
+    fn rejects_broken_samples() {
+        assert_eq!(parse_line("INFO  motion 8 10"), Err(InvalidSample));
+        assert_eq!(parse_line("INFO  motion 8 ten 0 52"), Err(InvalidSample));
+    }
+
+    #[test]
+    fn accumulates_due_samples() {
+        let samples = square();
+        assert_eq!(accumulate(&samples, 0), (0, 0, 0));
+        assert_eq!(accumulate(&samples, 31), (3, 120, 0));
+        assert_eq!(accumulate(&samples, 40), (5, 120, 80));
+    }
+
+    #[test]
+    fn replays_one_sample_per_burst_at_the_sample_rate() {
+        let samples = square();
+        let bursts: Vec<_> = replay(&samples, 8).collect();
+        let recorded: Vec<_> = samples.iter().map(|s| (s.dx, s.dy)).collect();
+        assert_eq!(bursts, recorded);
+    }
+
+    #[test]
+    fn replays_the_square_in_slower_bursts() {
+        let samples = square();
+        let bursts: Vec<_> = replay(&samples, 16).collect();
+        assert_eq!(
+            bursts,
+            [(80, 0), (40, 40), (0, 80), (-80, 0), (-40, -40), (0, -80)]
+        );
+        // Around the square and back to the start
+        let end = bursts
+            .iter()
+            .fold((0, 0), |(x, y), (dx, dy)| (x + dx, y + dy));
+        assert_eq!(end, (0, 0));
+    }
+
+    /// Replays the samples as motion bursts through the scroll code and returns the reports
+    /// sent, one chance to report per burst like the `ScrollController` polling at the rate of
+    /// the sensor reports
+    fn scroll(samples: &[MotionSample], interval_ms: u32) -> Vec<Option<(i8, i8)>> {
+        let mut accumulator = ScrollAccumulator::new();
+        replay(samples, interval_ms)
+            .map(|(dx, dy)| {
+                let [dx_lo, dx_hi] = dx.to_le_bytes();
+                let [dy_lo, dy_hi] = dy.to_le_bytes();
+                let mut burst = [MOTION_MOT, 0, dx_lo, dx_hi, dy_lo, dy_hi, 52];
+                if let Some((dx, dy)) = take_motion(&mut burst) {
+                    accumulator.add(dx, dy);
+                }
+                // The driver is left without motion to report
+                assert!(!has_motion(&burst));
+                accumulator.take()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scrolls_the_square() {
+        let reports = scroll(&square(), 8);
+        // Right pans left with the inverted X axis, down turns the wheel down
+        assert_eq!(
+            reports,
+            [
+                Some((0, -1)),
+                Some((0, -1)),
+                Some((0, -1)),
+                Some((-1, 0)),
+                Some((-1, 0)),
+                Some((-1, 0)),
+                None,
+                Some((0, 1)),
+                Some((0, 2)),
+                None,
+                Some((1, 0)),
+                Some((2, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrolls_the_square_in_slower_bursts() {
+        let reports = scroll(&square(), 16);
+        assert_eq!(
+            reports,
+            [
+                Some((0, -2)),
+                Some((-1, -1)),
+                Some((-2, 0)),
+                Some((0, 1)),
+                Some((0, 2)),
+                Some((3, 0)),
+            ]
+        );
+        // Around the square and back to the start
+        let end = reports
+            .iter()
+            .flatten()
+            .fold((0, 0), |(wheel, pan), (dw, dp)| (wheel + dw, pan + dp));
+        assert_eq!(end, (0, 0));
+    }
+
+    #[test]
+    fn saturates_instead_of_overflowing() {
+        let fast = [
+            MotionSample {
+                t_ms: 1,
+                dx: i16::MAX,
+                dy: i16::MIN,
+            },
+            MotionSample {
+                t_ms: 2,
+                dx: 1,
+                dy: -1,
+            },
+        ];
+        assert_eq!(accumulate(&fast, 2), (2, i16::MAX, i16::MIN));
+    }
+}
@@ -0,0 +1,82 @@
+//! Recording of the raw motion reported by the sensor.
+//!
+//! [`MotionTap`] sees every `Motion_Burst` read of the `Pmw33xx` driver on the `SensorBus`.
+//! With the `motion-trace` feature every burst with motion is logged over defmt as
+//! `motion <ms> <dx> <dy> <squal>`. Save the probe-rs output of a session as trace file, the
+//! host tests replay trace files from `traces/` (see `src/motionreplay.rs`).
+
+/// Register address that starts a motion burst read
+pub const MOTION_BURST: u8 = 0x50;
+
+/// Motion burst bytes up to `Delta_Y_H`
+pub const BURST_DELTA_LEN: usize = 6;
+/// Bit of the `Motion` register that flags new motion
+pub const MOTION_MOT: u8 = 0x80;
+
+/// Whether a motion burst read holds motion
+pub fn has_motion(burst: &[u8]) -> bool {
+    burst.len() >= BURST_DELTA_LEN
+        && burst[0] & MOTION_MOT != 0
+        && burst[2..BURST_DELTA_LEN].iter().any(|&delta| delta != 0)
+}
+
+pub struct MotionTap {
+    #[cfg(feature = "motion-trace")]
+    trace_start: Option<embassy_time::Instant>,
+}
+
+impl Default for MotionTap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MotionTap {
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(feature = "motion-trace")]
+            trace_start: None,
+        }
+    }
+
+    /// Called with the data of every motion burst read by the driver
+    #[allow(unused_variables)]
+    pub fn on_motion_burst(&mut self, burst: &[u8]) {
+        #[cfg(feature = "motion-trace")]
+        self.record(burst);
+    }
+
+    #[cfg(feature = "motion-trace")]
+    fn record(&mut self, burst: &[u8]) {
+        if !has_motion(burst) {
+            return;
+        }
+        let dx = i16::from_le_bytes([burst[2], burst[3]]);
+        let dy = i16::from_le_bytes([burst[4], burst[5]]);
+        let now = embassy_time::Instant::now();
+        let start = *self.trace_start.get_or_insert(now);
+        let squal = burst.get(6).copied().unwrap_or(0);
+        defmt::info!(
+            "motion {=u64} {=i16} {=i16} {=u8}",
+            (now - start).as_millis(),
+            dx,
+            dy,
+            squal
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_motion_in_bursts() {
+        assert!(has_motion(&[0x80, 0, 0xff, 0xff, 0, 0, 48]));
+        assert!(has_motion(&[0x80, 0, 0, 0, 1, 0]));
+        // No new motion flagged, or flagged without deltas
+        assert!(!has_motion(&[0x00, 0, 5, 0, 0, 0]));
+        assert!(!has_motion(&[0x80, 0, 0, 0, 0, 0]));
+        assert!(!has_motion(&[0x80, 0, 5]));
+    }
+}
@@ -0,0 +1,182 @@
+//! Short messages shown over the current page of the display for a while.
+//!
+//! Any processor on either half publishes a [`NotificationEvent`], the ones of the central reach
+//! the display over the split link. The display queues them in [`Notifications`] and shows one
+//! at a time.
+//!
+//! Published for the jiggle toggle, the CPI key, a failed sensor check, a storage reset at boot
+//! and the host suspending the USB bus.
+use embassy_time::{Duration, Instant};
+use rmk::heapless::Deque;
+use rmk_macro::event;
+
+/// Longest text, two lines of the small font on the display
+pub const NOTIFICATION_LEN: usize = 16;
+/// Notifications waiting while another one is shown, the oldest is dropped when full
+const QUEUE_LEN: usize = 4;
+/// Time a notification is shown unless its publisher wants it longer
+pub const NOTIFICATION_TTL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Notification {
+    text: [u8; NOTIFICATION_LEN],
+    len: u8,
+    /// Time the notification is shown
+    pub ttl_ms: u16,
+}
+
+impl Notification {
+    /// Notification of up to `NOTIFICATION_LEN` ASCII characters, longer texts are cut off
+    pub fn new(text: &str, ttl: Duration) -> Self {
+        let mut notification = Self {
+            text: [0; NOTIFICATION_LEN],
+            len: 0,
+            ttl_ms: ttl.as_millis().min(u16::MAX as u64) as u16,
+        };
+        for (i, c) in text.chars().take(NOTIFICATION_LEN).enumerate() {
+            notification.text[i] = if c.is_ascii() { c as u8 } else { b'?' };
+            notification.len += 1;
+        }
+        notification
+    }
+
+    /// Notification from its text bytes as sent over the split link
+    pub fn from_bytes(text: &[u8], ttl_ms: u16) -> Self {
+        let len = text.len().min(NOTIFICATION_LEN);
+        let mut notification = Self {
+            text: [0; NOTIFICATION_LEN],
+            len: len as u8,
+            ttl_ms,
+        };
+        for (i, &c) in text[..len].iter().enumerate() {
+            notification.text[i] = if c.is_ascii() { c } else { b'?' };
+        }
+        notification
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.text[..self.len as usize]
+    }
+
+    /// The text, ASCII only so it can be split anywhere
+    pub fn text(&self) -> &str {
+        core::str::from_utf8(self.bytes()).unwrap_or("?")
+    }
+}
+
+#[event(channel_size = 4)]
+#[derive(Clone, Copy, Debug)]
+pub struct NotificationEvent(pub Notification);
+
+/// The notification shown and the ones waiting
+pub struct Notifications {
+    shown: Option<(Notification, Instant)>,
+    queue: Deque<Notification, QUEUE_LEN>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifications {
+    pub const fn new() -> Self {
+        Self {
+            shown: None,
+            queue: Deque::new(),
+        }
+    }
+
+    pub fn push(&mut self, notification: Notification) {
+        if self.queue.is_full() {
+            self.queue.pop_front();
+        }
+        let _ = self.queue.push_back(notification);
+    }
+
+    /// The notification to show now, moves on to the next one when its time is up
+    pub fn current(&mut self) -> Option<Notification> {
+        if let Some((notification, since)) = self.shown {
+            if since.elapsed() < Duration::from_millis(notification.ttl_ms as u64) {
+                return Some(notification);
+            }
+            self.shown = None;
+        }
+        let notification = self.queue.pop_front()?;
+        self.shown = Some((notification, Instant::now()));
+        Some(notification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    fn notification(text: &str) -> Notification {
+        Notification::new(text, NOTIFICATION_TTL)
+    }
+
+    #[test]
+    fn shows_notifications_in_order() {
+        let mut notifications = Notifications::new();
+        assert_eq!(notifications.current(), None);
+        notifications.push(notification("FIRST"));
+        notifications.push(notification("SECOND"));
+        assert_eq!(notifications.current(), Some(notification("FIRST")));
+        // Still shown, the next one waits
+        assert_eq!(notifications.current(), Some(notification("FIRST")));
+    }
+
+    #[test]
+    fn drops_the_oldest_waiting_notification() {
+        let mut notifications = Notifications::new();
+        for text in ["1", "2", "3", "4", "5"] {
+            notifications.push(Notification::new(text, Duration::from_millis(0)));
+        }
+        let shown: Vec<_> = core::iter::from_fn(|| notifications.current())
+            .map(|notification| notification.text().to_owned())
+            .collect();
+        assert_eq!(shown, ["2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn moves_on_when_the_time_is_up() {
+        let mut notifications = Notifications::new();
+        let short = Notification::new("SHORT", Duration::from_millis(20));
+        notifications.push(short);
+        notifications.push(notification("NEXT"));
+        assert_eq!(notifications.current(), Some(short));
+        sleep(std::time::Duration::from_millis(30));
+        assert_eq!(notifications.current(), Some(notification("NEXT")));
+    }
+
+    #[test]
+    fn ends_after_the_last_one() {
+        let mut notifications = Notifications::new();
+        notifications.push(Notification::new("LAST", Duration::from_millis(20)));
+        assert!(notifications.current().is_some());
+        sleep(std::time::Duration::from_millis(30));
+        assert_eq!(notifications.current(), None);
+    }
+
+    #[test]
+    fn cuts_off_long_texts_and_replaces_non_ascii() {
+        assert_eq!(
+            notification("A VERY LONG NOTIFICATION").text(),
+            "A VERY LONG NOTI"
+        );
+        assert_eq!(notification("GRÜN").text(), "GR?N");
+        assert_eq!(
+            Notification::from_bytes(&[b'O', 0xc3, b'K'], 100).text(),
+            "O?K"
+        );
+    }
+
+    #[test]
+    fn caps_the_time_shown() {
+        let notification = Notification::new("LONG", Duration::from_secs(100));
+        assert_eq!(notification.ttl_ms, u16::MAX);
+    }
+}
@@ -0,0 +1,592 @@
+//! Buffered driver for the monochrome OLED controllers of our boards: SSD1306, SH1106 and
+//! SSD1309.
+//!
+//! The controllers share the command set for everything the pages need, they differ in the
+//! power supply and in how the frame is written: the SSD1306 and SSD1309 use horizontal
+//! addressing, the SH1106 only knows page addressing and has 132 columns of RAM with the panel
+//! in the middle. The pages draw into the buffer through [`DrawTarget`],
+//! [`Oled::flush`] sends the columns of each page that changed since the last flush.
+//!
+//! All of them are on the I2C bus of the peripheral, [`I2cInterface`] sends the commands and
+//! the display data.
+use display_interface::{AsyncWriteOnlyDataCommand, DataFormat, DataFormat::U8, DisplayError};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_hal_async::i2c::I2c;
+
+/// Largest supported panel, 128x64
+const BUFFER_LEN: usize = 128 * 64 / 8;
+
+/// I2C address of the controllers with their address pin low
+pub const I2C_ADDRESS: u8 = 0x3C;
+/// First byte of an I2C write, the rest are commands or display data
+const CONTROL_COMMANDS: u8 = 0x00;
+const CONTROL_DATA: u8 = 0x40;
+/// Bytes written per I2C transfer after the control byte
+const I2C_CHUNK_LEN: usize = 32;
+
+/// Commands and display data over I2C
+pub struct I2cInterface<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C: I2c> I2cInterface<I2C> {
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    /// Writes `bytes` in transfers starting with `control`
+    async fn write(&mut self, control: u8, bytes: DataFormat<'_>) -> Result<(), DisplayError> {
+        let U8(bytes) = bytes else {
+            return Err(DisplayError::DataFormatNotImplemented);
+        };
+        let mut transfer = [control; 1 + I2C_CHUNK_LEN];
+        for chunk in bytes.chunks(I2C_CHUNK_LEN) {
+            transfer[1..=chunk.len()].copy_from_slice(chunk);
+            self.i2c
+                .write(self.address, &transfer[..=chunk.len()])
+                .await
+                .map_err(|_| DisplayError::BusWriteError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C: I2c> AsyncWriteOnlyDataCommand for I2cInterface<I2C> {
+    async fn send_commands(&mut self, commands: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.write(CONTROL_COMMANDS, commands).await
+    }
+
+    async fn send_data(&mut self, data: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.write(CONTROL_DATA, data).await
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Controller {
+    Ssd1306,
+    Sh1106,
+    Ssd1309,
+}
+
+impl Controller {
+    /// Columns of the display RAM, the panel is centered in them
+    const fn ram_width(self) -> u32 {
+        match self {
+            Controller::Ssd1306 | Controller::Ssd1309 => 128,
+            Controller::Sh1106 => 132,
+        }
+    }
+}
+
+/// Rotation of the content, clockwise
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Rotation {
+    Rotate0,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+impl Rotation {
+    fn is_upright(self) -> bool {
+        matches!(self, Rotation::Rotate90 | Rotation::Rotate270)
+    }
+}
+
+pub struct Oled<DI> {
+    interface: DI,
+    controller: Controller,
+    rotation: Rotation,
+    /// Size of the panel before rotation
+    width: u32,
+    height: u32,
+    /// One bit per pixel in the layout of the display RAM: a byte is a column of 8 rows of a
+    /// page, the pages follow each other
+    buffer: [u8; BUFFER_LEN],
+    /// The buffer as last sent
+    sent: [u8; BUFFER_LEN],
+    /// The display RAM holds `sent`, false after an init or a failed flush
+    synced: bool,
+}
+
+impl<DI: AsyncWriteOnlyDataCommand> Oled<DI> {
+    /// A `width`x`height` panel, at most 128x64 with a height in multiples of 8
+    pub fn new(
+        interface: DI,
+        controller: Controller,
+        width: u32,
+        height: u32,
+        rotation: Rotation,
+    ) -> Self {
+        assert!(width <= 128 && height <= 64 && height.is_multiple_of(8));
+        Self {
+            interface,
+            controller,
+            rotation,
+            width,
+            height,
+            buffer: [0; BUFFER_LEN],
+            sent: [0; BUFFER_LEN],
+            synced: false,
+        }
+    }
+
+    /// Configures the controller and switches the display on
+    pub async fn init(&mut self) -> Result<(), DisplayError> {
+        self.synced = false;
+        self.command(&[0xAE]).await?;
+        // Clock divide ratio and oscillator frequency
+        self.command(&[0xD5, 0x80]).await?;
+        // Multiplex ratio
+        self.command(&[0xA8, self.height as u8 - 1]).await?;
+        // Display offset and start line
+        self.command(&[0xD3, 0x00]).await?;
+        self.command(&[0x40]).await?;
+        match self.controller {
+            // Internal charge pump on
+            Controller::Ssd1306 => self.command(&[0x8D, 0x14]).await?,
+            // Internal DC-DC converter on
+            Controller::Sh1106 => self.command(&[0xAD, 0x8B]).await?,
+            // Supplied by the panel
+            Controller::Ssd1309 => {}
+        }
+        if self.controller != Controller::Sh1106 {
+            // Horizontal addressing, the whole buffer is sent at once
+            self.command(&[0x20, 0x00]).await?;
+        }
+        // COM pins: sequential for the 32 rows panels, alternative otherwise
+        let com_pins = if self.height == 32 { 0x02 } else { 0x12 };
+        self.command(&[0xDA, com_pins]).await?;
+        // Segment remap and COM scan direction
+        let (segment, scan) = match self.rotation {
+            Rotation::Rotate0 => (0xA1, 0xC8),
+            Rotation::Rotate90 => (0xA0, 0xC8),
+            Rotation::Rotate180 => (0xA0, 0xC0),
+            Rotation::Rotate270 => (0xA1, 0xC0),
+        };
+        self.command(&[segment, scan]).await?;
+        self.set_brightness(0x2, 0x5F).await?;
+        // VCOMH deselect level, RAM content shown, not inverted, no scrolling
+        self.command(&[0xDB, 0x40]).await?;
+        self.command(&[0xA4, 0xA6, 0x2E]).await?;
+        self.set_display_on(true).await
+    }
+
+    /// Sets the phase 2 `precharge` period, 1 to 15, and the `contrast`
+    pub async fn set_brightness(
+        &mut self,
+        precharge: u8,
+        contrast: u8,
+    ) -> Result<(), DisplayError> {
+        self.command(&[0xD9, (precharge & 0xF) << 4 | 0x1]).await?;
+        self.command(&[0x81, contrast]).await
+    }
+
+    pub async fn set_display_on(&mut self, on: bool) -> Result<(), DisplayError> {
+        self.command(&[if on { 0xAF } else { 0xAE }]).await
+    }
+
+    pub fn clear_buffer(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// Sends the changes of the buffer to the display, all of it after an init or a failure
+    pub async fn flush(&mut self) -> Result<(), DisplayError> {
+        let result = self.flush_pages().await;
+        self.synced = result.is_ok();
+        result
+    }
+
+    async fn flush_pages(&mut self) -> Result<(), DisplayError> {
+        let width = self.width as usize;
+        let offset = ((self.controller.ram_width() - self.width) / 2) as u8;
+        for page in 0..self.height as usize / 8 {
+            let start = page * width;
+            let (first, last) = if self.synced {
+                let mut changed =
+                    (start..start + width).filter(|&i| self.buffer[i] != self.sent[i]);
+                let Some(first) = changed.next() else {
+                    continue;
+                };
+                (first, changed.next_back().unwrap_or(first))
+            } else {
+                (start, start + width - 1)
+            };
+
+            let column = offset + (first - start) as u8;
+            match self.controller {
+                Controller::Ssd1306 | Controller::Ssd1309 => {
+                    self.command(&[0x21, column, offset + (last - start) as u8])
+                        .await?;
+                    self.command(&[0x22, page as u8, page as u8]).await?;
+                }
+                // Page, then the lower and upper nibble of the column
+                Controller::Sh1106 => {
+                    self.command(&[0xB0 + page as u8, column & 0xF, 0x10 | column >> 4])
+                        .await?
+                }
+            }
+            self.interface
+                .send_data(U8(&self.buffer[first..=last]))
+                .await?;
+            self.sent[first..=last].copy_from_slice(&self.buffer[first..=last]);
+        }
+        Ok(())
+    }
+
+    async fn command(&mut self, bytes: &[u8]) -> Result<(), DisplayError> {
+        self.interface.send_commands(U8(bytes)).await
+    }
+}
+
+impl<DI> OriginDimensions for Oled<DI> {
+    /// Size of the content, after rotation
+    fn size(&self) -> Size {
+        if self.rotation.is_upright() {
+            Size::new(self.height, self.width)
+        } else {
+            Size::new(self.width, self.height)
+        }
+    }
+}
+
+impl<DI> DrawTarget for Oled<DI> {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.size();
+        for Pixel(point, color) in pixels {
+            let Ok((x, y)) = <(u32, u32)>::try_from(point) else {
+                continue;
+            };
+            if x >= size.width || y >= size.height {
+                continue;
+            }
+            // The rotation is done by the controller, upright content only swaps the axes
+            let (column, row) = if self.rotation.is_upright() {
+                (y, x)
+            } else {
+                (x, y)
+            };
+            let index = (row / 8 * self.width + column) as usize;
+            let bit = 1 << (row % 8);
+            if color.is_on() {
+                self.buffer[index] |= bit;
+            } else {
+                self.buffer[index] &= !bit;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embassy_futures::block_on;
+    use embedded_hal_async::i2c::{ErrorType, Operation};
+
+    /// Records the I2C writes
+    #[derive(Default)]
+    struct I2cLog {
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl ErrorType for I2cLog {
+        type Error = Infallible;
+    }
+
+    impl I2c for I2cLog {
+        async fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Infallible> {
+            for operation in operations {
+                if let Operation::Write(bytes) = operation {
+                    self.writes.push((address, bytes.to_vec()));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Sent {
+        Commands(Vec<u8>),
+        Data(Vec<u8>),
+    }
+
+    /// Records the commands and data sent to the controller
+    #[derive(Default)]
+    struct InterfaceLog {
+        sent: Vec<Sent>,
+        fail_data: bool,
+    }
+
+    impl AsyncWriteOnlyDataCommand for InterfaceLog {
+        async fn send_commands(&mut self, commands: DataFormat<'_>) -> Result<(), DisplayError> {
+            let U8(bytes) = commands else {
+                return Err(DisplayError::DataFormatNotImplemented);
+            };
+            self.sent.push(Sent::Commands(bytes.to_vec()));
+            Ok(())
+        }
+
+        async fn send_data(&mut self, data: DataFormat<'_>) -> Result<(), DisplayError> {
+            let U8(bytes) = data else {
+                return Err(DisplayError::DataFormatNotImplemented);
+            };
+            if self.fail_data {
+                return Err(DisplayError::BusWriteError);
+            }
+            self.sent.push(Sent::Data(bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    fn oled(controller: Controller, height: u32, rotation: Rotation) -> Oled<InterfaceLog> {
+        Oled::new(InterfaceLog::default(), controller, 128, height, rotation)
+    }
+
+    fn commands(oled: &Oled<InterfaceLog>) -> Vec<&[u8]> {
+        let sent = oled.interface.sent.iter();
+        sent.filter_map(|sent| match sent {
+            Sent::Commands(bytes) => Some(bytes.as_slice()),
+            Sent::Data(_) => None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn initializes_ssd1306() {
+        let mut oled = oled(Controller::Ssd1306, 32, Rotation::Rotate0);
+        block_on(oled.init()).unwrap();
+        let expected: &[&[u8]] = &[
+            &[0xAE],
+            &[0xD5, 0x80],
+            &[0xA8, 31],
+            &[0xD3, 0x00],
+            &[0x40],
+            &[0x8D, 0x14],
+            &[0x20, 0x00],
+            &[0xDA, 0x02],
+            &[0xA1, 0xC8],
+            &[0xD9, 0x21],
+            &[0x81, 0x5F],
+            &[0xDB, 0x40],
+            &[0xA4, 0xA6, 0x2E],
+            &[0xAF],
+        ];
+        assert_eq!(commands(&oled), expected);
+    }
+
+    #[test]
+    fn powers_each_controller() {
+        for (controller, supply) in [
+            (Controller::Ssd1306, Some([0x8D, 0x14])),
+            (Controller::Sh1106, Some([0xAD, 0x8B])),
+            (Controller::Ssd1309, None),
+        ] {
+            let mut oled = oled(controller, 64, Rotation::Rotate0);
+            block_on(oled.init()).unwrap();
+            let commands = commands(&oled);
+            // The supply follows the start line, directly before the addressing or COM pins
+            let after_start_line = commands[5];
+            match supply {
+                Some(supply) => assert_eq!(after_start_line, supply, "{controller:?}"),
+                None => assert!(
+                    !commands.iter().any(|c| c[0] == 0x8D || c[0] == 0xAD),
+                    "{controller:?}"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn sets_horizontal_addressing_except_on_sh1106() {
+        for (controller, addressing) in [
+            (Controller::Ssd1306, true),
+            (Controller::Sh1106, false),
+            (Controller::Ssd1309, true),
+        ] {
+            let mut oled = oled(controller, 64, Rotation::Rotate0);
+            block_on(oled.init()).unwrap();
+            let found = commands(&oled).contains(&&[0x20, 0x00][..]);
+            assert_eq!(found, addressing, "{controller:?}");
+        }
+    }
+
+    #[test]
+    fn remaps_segments_and_scan_for_the_rotation() {
+        for (rotation, remap) in [
+            (Rotation::Rotate0, [0xA1, 0xC8]),
+            (Rotation::Rotate90, [0xA0, 0xC8]),
+            (Rotation::Rotate180, [0xA0, 0xC0]),
+            (Rotation::Rotate270, [0xA1, 0xC0]),
+        ] {
+            let mut oled = oled(Controller::Ssd1306, 64, rotation);
+            block_on(oled.init()).unwrap();
+            let commands = commands(&oled);
+            let com_pins = commands.iter().position(|c| c[0] == 0xDA).unwrap();
+            assert_eq!(commands[com_pins + 1], remap, "{rotation:?}");
+        }
+    }
+
+    #[test]
+    fn addresses_sh1106_pages_with_the_column_offset() {
+        let mut oled = oled(Controller::Sh1106, 64, Rotation::Rotate0);
+        block_on(oled.flush()).unwrap();
+
+        // 128 of the 132 columns, centered: column 2 is the first one of the panel
+        let sent = &oled.interface.sent;
+        assert_eq!(sent.len(), 2 * 8);
+        for (page, sent) in sent.chunks(2).enumerate() {
+            assert_eq!(sent[0], Sent::Commands(vec![0xB0 + page as u8, 0x02, 0x10]));
+            assert_eq!(sent[1], Sent::Data(vec![0; 128]));
+        }
+    }
+
+    #[test]
+    fn splits_sh1106_columns_into_nibbles() {
+        let mut oled = oled(Controller::Sh1106, 64, Rotation::Rotate0);
+        block_on(oled.flush()).unwrap();
+        oled.interface.sent.clear();
+        Pixel(Point::new(30, 0), BinaryColor::On)
+            .draw(&mut oled)
+            .unwrap();
+        block_on(oled.flush()).unwrap();
+
+        // Column 30 + 2 = 0x20
+        assert_eq!(
+            oled.interface.sent,
+            [
+                Sent::Commands(vec![0xB0, 0x00, 0x12]),
+                Sent::Data(vec![0x01])
+            ]
+        );
+    }
+
+    /// The data sent by the last flush, `(page, first column, bytes)`
+    fn flushed(oled: &mut Oled<InterfaceLog>) -> Vec<(u8, u8, Vec<u8>)> {
+        oled.interface.sent.clear();
+        block_on(oled.flush()).unwrap();
+        let sent = oled.interface.sent.chunks(3);
+        sent.map(|sent| match sent {
+            [Sent::Commands(columns), Sent::Commands(pages), Sent::Data(data)] => {
+                assert_eq!(columns[0], 0x21);
+                assert_eq!(columns[2] - columns[1] + 1, data.len() as u8);
+                assert_eq!(pages[..2], [0x22, pages[2]]);
+                (pages[1], columns[1], data.clone())
+            }
+            other => panic!("Unexpected flush {other:?}"),
+        })
+        .collect()
+    }
+
+    fn full_frame(height: u8) -> Vec<(u8, u8, Vec<u8>)> {
+        (0..height / 8)
+            .map(|page| (page, 0, vec![0; 128]))
+            .collect()
+    }
+
+    #[test]
+    fn sends_nothing_for_an_unchanged_buffer() {
+        let mut oled = oled(Controller::Ssd1306, 32, Rotation::Rotate0);
+        assert_eq!(flushed(&mut oled), full_frame(32));
+        assert_eq!(flushed(&mut oled), []);
+    }
+
+    #[test]
+    fn sends_only_the_changed_span_of_a_page() {
+        let mut oled = oled(Controller::Ssd1306, 32, Rotation::Rotate0);
+        flushed(&mut oled);
+        Pixel(Point::new(40, 17), BinaryColor::On)
+            .draw(&mut oled)
+            .unwrap();
+        assert_eq!(flushed(&mut oled), [(2, 40, vec![0x02])]);
+
+        Pixel(Point::new(10, 16), BinaryColor::On)
+            .draw(&mut oled)
+            .unwrap();
+        Pixel(Point::new(13, 16), BinaryColor::On)
+            .draw(&mut oled)
+            .unwrap();
+        assert_eq!(flushed(&mut oled), [(2, 10, vec![0x01, 0, 0, 0x01])]);
+    }
+
+    #[test]
+    fn resends_everything_after_init() {
+        let mut oled = oled(Controller::Ssd1306, 32, Rotation::Rotate0);
+        flushed(&mut oled);
+        block_on(oled.init()).unwrap();
+        assert_eq!(flushed(&mut oled), full_frame(32));
+    }
+
+    #[test]
+    fn resends_everything_after_a_failed_flush() {
+        let mut oled = oled(Controller::Ssd1306, 32, Rotation::Rotate0);
+        flushed(&mut oled);
+        Pixel(Point::new(0, 0), BinaryColor::On)
+            .draw(&mut oled)
+            .unwrap();
+        oled.interface.fail_data = true;
+        assert!(matches!(
+            block_on(oled.flush()),
+            Err(DisplayError::BusWriteError)
+        ));
+
+        oled.interface.fail_data = false;
+        let mut expected = full_frame(32);
+        expected[0].2[0] = 0x01;
+        assert_eq!(flushed(&mut oled), expected);
+    }
+
+    #[test]
+    fn prefixes_commands_and_data() {
+        let mut interface = I2cInterface::new(I2cLog::default(), I2C_ADDRESS);
+        block_on(async {
+            interface.send_commands(U8(&[0x21, 0, 127])).await.unwrap();
+            interface.send_data(U8(&[0xaa, 0x55])).await.unwrap();
+        });
+        assert_eq!(
+            interface.i2c.writes,
+            [
+                (0x3c, vec![0x00, 0x21, 0, 127]),
+                (0x3c, vec![0x40, 0xaa, 0x55])
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_long_data() {
+        let mut interface = I2cInterface::new(I2cLog::default(), I2C_ADDRESS);
+        let page: Vec<u8> = (0..128).collect();
+        block_on(interface.send_data(U8(&page))).unwrap();
+
+        let writes = &interface.i2c.writes;
+        assert_eq!(writes.len(), 128 / I2C_CHUNK_LEN);
+        for (chunk, (_, write)) in page.chunks(I2C_CHUNK_LEN).zip(writes) {
+            assert_eq!(write[0], CONTROL_DATA);
+            assert_eq!(&write[1..], chunk);
+        }
+    }
+
+    #[test]
+    fn rejects_other_data_formats() {
+        let mut interface = I2cInterface::new(I2cLog::default(), I2C_ADDRESS);
+        let result = block_on(interface.send_data(DataFormat::U16(&[0xaa55])));
+        assert!(matches!(
+            result,
+            Err(DisplayError::DataFormatNotImplemented)
+        ));
+        assert!(interface.i2c.writes.is_empty());
+    }
+}
@@ -5,12 +5,11 @@
 mod macros;
 
 use defmt::*;
-use embassy_embedded_hal::adapter::BlockingAsync;
 use embassy_executor::Spawner;
 use embassy_rp::bind_interrupts;
 use embassy_rp::gpio::{Input, Output};
-use embassy_rp::i2c::I2c;
-use embassy_rp::peripherals::{UART0, USB};
+use embassy_rp::i2c::{self, I2c};
+use embassy_rp::peripherals::{I2C1, UART0, USB};
 use embassy_rp::uart::{self, BufferedUart};
 use embassy_rp::usb::InterruptHandler;
 use rmk::debounce::default_debouncer::DefaultDebouncer;
@@ -22,16 +21,39 @@ use rmk::split::SPLIT_MESSAGE_MAX_SIZE;
 use static_cell::StaticCell;
 use {defmt_rtt as _, panic_probe as _};
 
+pub mod activitystate;
+pub mod animation;
+#[cfg(feature = "pet-cat")]
+pub mod catsprites;
+pub mod displaycommands;
+pub mod displayconfig;
+use displayconfig::{DISPLAY_CONTROLLER, DISPLAY_HEIGHT, DISPLAY_ROTATION, DISPLAY_WIDTH};
+pub mod displaylayout;
+pub mod displaypages;
+pub mod displaysettings;
+pub mod flashstore;
 pub mod jigglemode;
+pub mod linkstate;
+pub mod modifierstate;
+pub mod notification;
+pub mod oled;
+use oled::{I2cInterface, Oled, I2C_ADDRESS};
+pub mod pointingstate;
+pub mod sensorstate;
+pub mod splitframe;
+pub mod splitlink;
+use splitlink::{SplitLink, SplitLinkWriter, SplitTx};
+pub mod sprites;
 pub mod ssd1306cont;
 use ssd1306cont::Ssd1306Controller;
-
-// graphics
-use ssd1306::{prelude::*, I2CDisplayInterface, Ssd1306Async};
+pub mod typingstate;
+pub mod usbstate;
+pub mod wpmhistory;
 
 bind_interrupts!(struct Irqs {
     USBCTRL_IRQ => InterruptHandler<USB>;
     UART0_IRQ => uart::BufferedInterruptHandler<UART0>;
+    I2C1_IRQ => i2c::InterruptHandler<I2C1>;
 });
 
 #[embassy_executor::main]
@@ -59,28 +81,37 @@ async fn main(_spawner: Spawner) {
         rx_buf,
         uart::Config::default(),
     );
+    // rmk reads through the split link, its frames and ours share the sending half
+    let (uart_tx, uart_rx) = uart_instance.split();
+    let split_tx = SplitTx::new(uart_tx);
+    let mut split_writer = SplitLinkWriter::new(&split_tx);
 
     // Define the matrix
     let debouncer = DefaultDebouncer::new();
     let mut matrix = Matrix::<_, _, _, 6, 6, true, 0, 0>::new(row_pins, col_pins, debouncer);
 
     // display driver
-    let config = embassy_rp::i2c::Config::default();
-    let i2c = I2c::new_blocking(p.I2C1, p.PIN_3, p.PIN_2, config);
-    let i2c = BlockingAsync::new(i2c);
+    // Interrupt driven, the executor keeps scanning the matrix during a flush
+    let config = i2c::Config::default();
+    let i2c = I2c::new_async(p.I2C1, p.PIN_3, p.PIN_2, Irqs, config);
 
     // Create display interface with default I2C address 0x3C
-    let interface = I2CDisplayInterface::new(i2c);
-    let mut display = Ssd1306Async::new(interface, DisplaySize128x32, DisplayRotation::Rotate90)
-        .into_buffered_graphics_mode();
-    display.init().await.unwrap();
+    let interface = I2cInterface::new(i2c, I2C_ADDRESS);
+    let display = Oled::new(
+        interface,
+        DISPLAY_CONTROLLER,
+        DISPLAY_WIDTH,
+        DISPLAY_HEIGHT,
+        DISPLAY_ROTATION,
+    );
 
     let mut ssd1306cont = Ssd1306Controller::new(display);
 
     // Start
     join_all!(
         run_all!(matrix, ssd1306cont),
-        run_rmk_split_peripheral(uart_instance)
+        split_writer.run(),
+        run_rmk_split_peripheral(SplitLink::new(uart_rx, &split_tx))
     )
     .await;
 }
@@ -1,258 +0,0 @@
-pub const PMW3360_SROM: &[u8] = &[
-    0x01, 0x05, 0x8d, 0x92, 0x66, 0x67, 0x1e, 0xbe, 0xfe, 0x5f, 0x1d, 0xb8, 0xf2, 0x66, 0x4e, 0xff,
-    0x5d, 0x38, 0xf2, 0x46, 0x0c, 0x98, 0x97, 0x8d, 0x79, 0x51, 0x20, 0xc2, 0x06, 0x8e, 0x9e, 0xbe,
-    0xfe, 0x7e, 0x5f, 0x1d, 0xb8, 0xd3, 0x24, 0xca, 0x16, 0x8f, 0x7d, 0x78, 0x53, 0x05, 0x88, 0x73,
-    0x45, 0xe9, 0x55, 0x22, 0xa7, 0xae, 0xd8, 0x32, 0xe6, 0x2f, 0xbd, 0xf8, 0x72, 0x47, 0x0c, 0x7b,
-    0x74, 0x6a, 0x56, 0x2e, 0xbf, 0xdd, 0x38, 0xd5, 0x20, 0xca, 0x16, 0xae, 0xde, 0x1f, 0x9d, 0xb8,
-    0xf2, 0x47, 0x0c, 0x7b, 0x55, 0x28, 0xd2, 0x26, 0xaf, 0xdc, 0x3a, 0xd7, 0x2c, 0xbb, 0xd5, 0x09,
-    0x90, 0xa2, 0xa7, 0xcc, 0xfb, 0x74, 0x4b, 0xf5, 0x49, 0x10, 0x83, 0x84, 0x8a, 0x77, 0x4d, 0x18,
-    0xb2, 0xe6, 0x4e, 0x1e, 0x9f, 0xbc, 0xfa, 0x6a, 0xea, 0xab, 0x55, 0x91, 0xa9, 0xda, 0x58, 0xa9,
-    0xe1, 0x1a, 0xc2, 0x52, 0x1c, 0x0d, 0xef, 0x50, 0x71, 0x04, 0x21, 0x93, 0x87, 0x0e, 0xb1, 0xa9,
-    0x4a, 0xc0, 0x24, 0xaf, 0xe3, 0x26, 0xa2, 0x8d, 0xa1, 0x6d, 0xf2, 0x88, 0x9e, 0x99, 0xbb, 0xf2,
-    0x62, 0x38, 0xfe, 0x78, 0x1d, 0x58, 0x19, 0x9b, 0xbe, 0x10, 0x6c, 0x15, 0x9e, 0x50, 0x8b, 0x99,
-    0x40, 0x92, 0x01, 0x38, 0xb6, 0x97, 0x83, 0x51, 0x59, 0xa6, 0xb7, 0xc9, 0x48, 0xfd, 0x68, 0x21,
-    0x78, 0xf4, 0x2e, 0x86, 0x61, 0xb0, 0xb2, 0x2b, 0x5b, 0xf0, 0x1b, 0x71, 0xa3, 0x0a, 0xd5, 0xd6,
-    0x30, 0x7a, 0x0c, 0xf8, 0xaf, 0xc6, 0x54, 0x6b, 0x89, 0x30, 0x58, 0xe8, 0xe2, 0x0d, 0x39, 0x2f,
-    0x47, 0xa0, 0x41, 0x74, 0x5d, 0xd6, 0xf3, 0x85, 0xb7, 0x66, 0x59, 0x51, 0x74, 0x3d, 0xfe, 0xee,
-    0x3a, 0x1e, 0x2a, 0x5f, 0x93, 0x0c, 0x3f, 0xd4, 0x4d, 0x24, 0x13, 0x2c, 0xf2, 0x7b, 0x4d, 0x06,
-    0x60, 0x2e, 0x39, 0xa9, 0xd4, 0x72, 0x36, 0xb6, 0x4d, 0xea, 0x8c, 0xd9, 0xa7, 0xbc, 0x38, 0xe7,
-    0xbb, 0xb7, 0x7b, 0x04, 0x08, 0x83, 0x5a, 0x27, 0x6f, 0x42, 0xc6, 0x7e, 0xbd, 0x97, 0x0e, 0x55,
-    0xdc, 0x79, 0x63, 0xd8, 0x89, 0xc1, 0x83, 0x3d, 0x34, 0x01, 0x76, 0xff, 0xde, 0x7f, 0x65, 0x4b,
-    0xb3, 0x46, 0x56, 0x8d, 0x9e, 0x6f, 0x1f, 0x2b, 0xa4, 0x69, 0x54, 0xde, 0x1c, 0x2d, 0xa8, 0x51,
-    0x7e, 0xdf, 0xbb, 0x18, 0x90, 0x27, 0xaf, 0x79, 0xd1, 0x86, 0x4f, 0x44, 0x88, 0x7f, 0xb9, 0x80,
-    0x21, 0xa9, 0x5d, 0x7b, 0xd4, 0x81, 0x21, 0xa9, 0x13, 0x59, 0x72, 0x58, 0x11, 0xe3, 0xf2, 0xf7,
-    0xae, 0xc3, 0xc2, 0x64, 0x1d, 0xfb, 0xd8, 0x91, 0x2d, 0x13, 0xb5, 0x0e, 0x67, 0xdb, 0xbc, 0x63,
-    0xde, 0xa0, 0x86, 0x49, 0x6c, 0x40, 0x82, 0x82, 0x3f, 0xeb, 0x38, 0x22, 0xa8, 0x20, 0x1e, 0xc5,
-    0x5f, 0x25, 0xea, 0xaf, 0xe6, 0x78, 0x1c, 0x41, 0x0b, 0x5a, 0x94, 0xcc, 0x8d, 0x0d, 0xc5, 0xb8,
-    0x9c, 0x1a, 0x9f, 0x6d, 0xa8, 0x57, 0xe9, 0x1a, 0xf0, 0xad, 0x6c, 0x26, 0x5f, 0xb8, 0x38, 0x16,
-    0x0e, 0x28, 0xd6, 0x76, 0x5a, 0xc2, 0xb7, 0x6a, 0x9e, 0x26, 0x9d, 0x3e, 0x1f, 0x25, 0xb0, 0x14,
-    0x46, 0xcd, 0x67, 0x49, 0x93, 0x13, 0x81, 0x01, 0x46, 0x72, 0x31, 0x42, 0x91, 0x87, 0x6c, 0x1c,
-    0xb8, 0x08, 0x72, 0x6a, 0x33, 0x64, 0xed, 0xc0, 0x99, 0xb9, 0xf6, 0x34, 0xe9, 0xa5, 0x6c, 0x57,
-    0x9a, 0x72, 0x18, 0x48, 0x62, 0xc1, 0x88, 0xcf, 0xa3, 0x50, 0x53, 0x50, 0xdf, 0xc8, 0x9f, 0xf0,
-    0xb6, 0xa2, 0x23, 0xc4, 0xaa, 0xe3, 0xd0, 0xe2, 0xab, 0x76, 0xc8, 0x4a, 0x02, 0x21, 0xdd, 0xf3,
-    0x59, 0xdc, 0x30, 0x6e, 0xb5, 0x42, 0xe2, 0x9d, 0x8a, 0xdf, 0xfd, 0xa5, 0x41, 0x75, 0x01, 0x83,
-    0xbe, 0x47, 0xa5, 0x21, 0x30, 0x3b, 0x0e, 0x33, 0x2e, 0x74, 0xaf, 0x27, 0x90, 0xf4, 0x20, 0xa7,
-    0x1b, 0xff, 0x77, 0x01, 0xab, 0x79, 0x0a, 0xfb, 0x86, 0x1e, 0x1f, 0x22, 0x6d, 0x75, 0x75, 0xfc,
-    0xe0, 0x8c, 0xa2, 0x72, 0x0f, 0x45, 0x48, 0xf9, 0x43, 0x10, 0xf0, 0x57, 0x15, 0x17, 0x74, 0x40,
-    0x8f, 0x77, 0x69, 0x8a, 0xd4, 0x0f, 0x25, 0x27, 0x0a, 0xe8, 0x84, 0x73, 0x41, 0xb9, 0x5f, 0xb6,
-    0xc8, 0x2f, 0x02, 0x83, 0xdc, 0x1e, 0x1c, 0xdb, 0x3f, 0x2d, 0x58, 0xf7, 0x5e, 0xfd, 0x6f, 0xda,
-    0xad, 0xdc, 0x43, 0x01, 0x22, 0xc6, 0x45, 0x9c, 0xec, 0x56, 0x8a, 0x1a, 0x4e, 0xaa, 0xae, 0x3a,
-    0xa1, 0xf3, 0xf1, 0x9a, 0x97, 0x8d, 0x79, 0x51, 0x01, 0xf5, 0x31, 0x04, 0xcd, 0x2a, 0xbc, 0xfd,
-    0xac, 0x59, 0x1b, 0x0a, 0xe2, 0x26, 0xdd, 0x2f, 0x96, 0xaa, 0x62, 0xc5, 0x6d, 0xb8, 0xe1, 0x72,
-    0x4e, 0x4e, 0xb7, 0x4f, 0x9f, 0xaa, 0x33, 0x75, 0x8b, 0x43, 0x44, 0x0a, 0xc3, 0x0a, 0x5e, 0x9c,
-    0xdb, 0x1e, 0x4e, 0xbd, 0x0f, 0xec, 0x09, 0x96, 0x5a, 0x37, 0x6d, 0x10, 0x44, 0xd4, 0x91, 0xc0,
-    0xc3, 0x0d, 0x35, 0x62, 0xcf, 0x0a, 0xb1, 0x1a, 0x8a, 0xda, 0xa8, 0xcf, 0xbc, 0x3a, 0xb2, 0x4f,
-    0x59, 0x29, 0xd9, 0x91, 0x96, 0x0d, 0x6b, 0x9c, 0xcf, 0x34, 0x5b, 0x76, 0x7b, 0xb2, 0xb2, 0xce,
-    0xbf, 0x72, 0xd9, 0x96, 0x4a, 0xc0, 0x2d, 0x89, 0x33, 0xd0, 0xe4, 0xbe, 0x69, 0x7f, 0x1c, 0x9e,
-    0x71, 0x19, 0x12, 0xf6, 0x74, 0xfb, 0xd6, 0x41, 0x95, 0x69, 0xf0, 0xe9, 0xa7, 0x50, 0x82, 0x04,
-    0x78, 0x4d, 0x99, 0xb3, 0xa7, 0xfe, 0xee, 0x7f, 0xed, 0x56, 0x57, 0x57, 0xe9, 0xd3, 0x6a, 0x43,
-    0xf4, 0x0b, 0x6d, 0xba, 0x34, 0xde, 0xf8, 0x4c, 0x45, 0xae, 0x00, 0x32, 0x1a, 0x4e, 0xff, 0x54,
-    0x60, 0x6a, 0xd9, 0x59, 0x18, 0xd3, 0xbe, 0x5a, 0x94, 0x16, 0x9d, 0xec, 0xdf, 0xb4, 0x48, 0x8e,
-    0x6f, 0x19, 0xf8, 0xe6, 0xec, 0x7f, 0xb7, 0x49, 0x92, 0xb3, 0xb6, 0xec, 0x7e, 0xdc, 0x08, 0x3b,
-    0xe5, 0x5b, 0x88, 0xf9, 0x92, 0xf3, 0x7c, 0x9d, 0x7c, 0x22, 0xf2, 0x5d, 0x7d, 0x76, 0x07, 0x2e,
-    0x42, 0xd6, 0x9e, 0x5a, 0xbc, 0x7d, 0xfd, 0xb5, 0x92, 0x4a, 0x7f, 0xbf, 0xac, 0xc0, 0xb6, 0x18,
-    0x8b, 0x02, 0x71, 0xae, 0xc7, 0xa8, 0x8c, 0x19, 0xc1, 0x0a, 0x36, 0x3e, 0xa4, 0x2d, 0xa6, 0xbb,
-    0xe8, 0x77, 0x34, 0x45, 0xca, 0x60, 0x52, 0xf0, 0x26, 0xf7, 0x58, 0x68, 0x34, 0x28, 0xb5, 0x29,
-    0xe5, 0xb8, 0x65, 0x0f, 0xc8, 0xc2, 0x64, 0x50, 0x91, 0x12, 0xa1, 0x7b, 0xd7, 0x09, 0x74, 0x5f,
-    0xcc, 0xac, 0x9d, 0xac, 0x0a, 0xd1, 0x46, 0xbe, 0xc2, 0x2d, 0xa6, 0x67, 0x69, 0x69, 0x55, 0x8a,
-    0xbd, 0x67, 0xd9, 0x31, 0xc7, 0x80, 0x7c, 0xd5, 0xb2, 0xbe, 0x83, 0x17, 0x7a, 0x2e, 0xf4, 0x3e,
-    0x14, 0xa5, 0x62, 0x77, 0xa5, 0xdb, 0x77, 0x58, 0x35, 0x5f, 0xc4, 0x6c, 0xd2, 0xf2, 0x38, 0x91,
-    0x74, 0x80, 0x95, 0x52, 0xd2, 0xaf, 0xe2, 0x1b, 0x12, 0xfe, 0x4b, 0x5f, 0x84, 0x5f, 0x7d, 0x7e,
-    0x0b, 0xd9, 0x42, 0x72, 0xf1, 0x32, 0x1d, 0x8d, 0x5c, 0xcb, 0x20, 0x05, 0x4f, 0xbe, 0xbf, 0x40,
-    0xe9, 0xa6, 0x9f, 0xff, 0x05, 0xe1, 0x73, 0x52, 0x65, 0xb4, 0x59, 0x45, 0x04, 0x10, 0xe1, 0xdc,
-    0x0b, 0x30, 0xaa, 0x23, 0x4d, 0xb9, 0x58, 0xe5, 0xf1, 0xa3, 0x9c, 0xf2, 0xf7, 0xaf, 0xbc, 0xe3,
-    0xa7, 0x0d, 0x68, 0x97, 0x6c, 0x2b, 0x33, 0x60, 0xe3, 0xce, 0x7c, 0xda, 0xd1, 0x2d, 0x78, 0x52,
-    0x10, 0xa0, 0xf7, 0x9a, 0xf4, 0x79, 0xdb, 0xa5, 0x2a, 0x71, 0xc6, 0xcf, 0xa0, 0x68, 0xd0, 0x32,
-    0x2a, 0xd3, 0xb2, 0xe8, 0x03, 0x27, 0xbe, 0x55, 0xfd, 0x0e, 0x90, 0xa2, 0x76, 0x8c, 0xdc, 0xbd,
-    0x69, 0x9c, 0xb6, 0x18, 0x92, 0xe3, 0xb3, 0x23, 0x1d, 0x3c, 0x85, 0x2a, 0x2b, 0x04, 0x3a, 0xf5,
-    0xee, 0xf8, 0xd2, 0xdd, 0x6d, 0x52, 0xa2, 0x45, 0x75, 0xd9, 0xa1, 0x03, 0x23, 0x83, 0x24, 0x04,
-    0xe7, 0xee, 0xa2, 0x14, 0x4e, 0xa9, 0xde, 0x4e, 0x9d, 0x24, 0x1a, 0x63, 0xd3, 0x4b, 0xf7, 0x71,
-    0xc1, 0xe7, 0xfb, 0x48, 0xa4, 0x7c, 0x39, 0xcb, 0x80, 0xdd, 0x5e, 0xaf, 0x0d, 0xca, 0xef, 0x58,
-    0x17, 0xf4, 0xca, 0x0c, 0x00, 0x30, 0x68, 0x3e, 0x5d, 0x9b, 0xc3, 0x19, 0x27, 0x7a, 0x35, 0x32,
-    0x5c, 0x7b, 0x92, 0x16, 0x7f, 0x6e, 0x86, 0x6b, 0x50, 0x7a, 0xd6, 0x15, 0x10, 0x16, 0xa8, 0x28,
-    0xb1, 0xa1, 0xd8, 0xda, 0xc8, 0xfd, 0x5b, 0xe2, 0xc0, 0xd6, 0x8d, 0x75, 0xbd, 0x1c, 0x0b, 0x27,
-    0xfe, 0x1d, 0x09, 0x23, 0x13, 0x4f, 0x5a, 0x5a, 0xf8, 0xbe, 0x5d, 0x31, 0x8a, 0x81, 0xaf, 0xbe,
-    0xed, 0x00, 0x43, 0x14, 0x8f, 0xa5, 0x4a, 0xfe, 0x90, 0xe1, 0x67, 0x25, 0x29, 0x52, 0x2f, 0x26,
-    0x17, 0xef, 0x6e, 0xe6, 0x1e, 0xaf, 0x7f, 0x4a, 0x40, 0x15, 0xc7, 0x6e, 0x8d, 0x60, 0x92, 0xd6,
-    0x8d, 0x95, 0xca, 0xff, 0x8e, 0xfe, 0x4b, 0x8d, 0x1a, 0x83, 0x40, 0xe6, 0xb7, 0xbc, 0x6b, 0xd6,
-    0x39, 0x53, 0xd0, 0x4a, 0xe6, 0x1f, 0x1f, 0xb1, 0x16, 0x6a, 0xae, 0x6a, 0x6d, 0xe3, 0xe6, 0xed,
-    0x4e, 0xff, 0x49, 0x28, 0xc7, 0xaf, 0xb4, 0x14, 0x9f, 0x85, 0x9f, 0xfe, 0x6a, 0x92, 0x93, 0x9c,
-    0x6f, 0xff, 0x4a, 0xd4, 0x3e, 0x26, 0xd9, 0x72, 0x4f, 0x01, 0xe2, 0x54, 0x06, 0xc6, 0x7d, 0xb8,
-    0xef, 0x0b, 0x04, 0x56, 0x3a, 0x51, 0x41, 0xe2, 0x1d, 0xc2, 0x96, 0x95, 0xd1, 0x66, 0x16, 0x3b,
-    0x50, 0x36, 0x3f, 0x2e, 0xf2, 0xb2, 0x25, 0x2d, 0x59, 0x0f, 0x23, 0xf2, 0x9d, 0xb0, 0xee, 0x59,
-    0x89, 0xd0, 0x79, 0x30, 0xc7, 0x54, 0x9b, 0xec, 0xf8, 0xd7, 0xdc, 0x5a, 0x11, 0x1d, 0xfb, 0xf0,
-    0x82, 0x33, 0x3c, 0xff, 0x17, 0xff, 0xfc, 0x92, 0xc6, 0x6f, 0x3d, 0x5d, 0x99, 0x85, 0x2a, 0xa1,
-    0x04, 0xe8, 0x21, 0xed, 0x12, 0x2b, 0x7d, 0xbc, 0xa3, 0x36, 0x9b, 0x9f, 0xfd, 0x5d, 0x60, 0x66,
-    0xe9, 0x39, 0x5e, 0x34, 0x29, 0xa7, 0x08, 0x56, 0xf7, 0x4d, 0x19, 0x8d, 0x91, 0xff, 0xc8, 0x8a,
-    0xe7, 0xe8, 0x22, 0x00, 0x35, 0x68, 0x34, 0x93, 0xe7, 0x3b, 0x41, 0xbb, 0xf6, 0x58, 0x2f, 0x10,
-    0x66, 0x77, 0x69, 0xcd, 0x94, 0xb9, 0xbe, 0x59, 0x73, 0x4f, 0xc3, 0x11, 0xa1, 0xb3, 0x13, 0xe2,
-    0x82, 0xf1, 0x05, 0xcb, 0xa7, 0x75, 0xf6, 0x01, 0xb2, 0x24, 0xa9, 0x59, 0x05, 0x59, 0x46, 0xc9,
-    0x25, 0x39, 0x36, 0xee, 0x48, 0x90, 0x83, 0x89, 0xc4, 0xdb, 0xa3, 0x83, 0xd0, 0xd3, 0x62, 0x3f,
-    0x5e, 0x0e, 0x0c, 0xb7, 0x77, 0xb3, 0xd6, 0xbb, 0x37, 0x70, 0xb2, 0xb3, 0x87, 0x60, 0xd7, 0x8a,
-    0xe2, 0x99, 0xa9, 0x0f, 0x18, 0x16, 0xf6, 0xcd, 0x14, 0x4e, 0x2c, 0x7e, 0xfe, 0xf9, 0xa4, 0x48,
-    0xfb, 0xb9, 0x60, 0x13, 0x01, 0xd2, 0x6e, 0xdc, 0x3f, 0xe7, 0x2d, 0x7f, 0xe1, 0x11, 0x96, 0x12,
-    0x9a, 0x90, 0x48, 0x2b, 0x76, 0x78, 0xa6, 0xdd, 0x67, 0x48, 0x68, 0x42, 0xfd, 0x74, 0xb6, 0x51,
-    0xd7, 0x99, 0x07, 0x7d, 0x8b, 0x02, 0xd6, 0x68, 0x36, 0x97, 0x6d, 0x49, 0x28, 0xbf, 0xcc, 0x23,
-    0x8f, 0xed, 0x78, 0x4c, 0x49, 0xb0, 0xd1, 0x9a, 0x3d, 0x1c, 0xdd, 0xf0, 0xeb, 0xd5, 0xd4, 0xc2,
-    0x12, 0xae, 0x5d, 0x00, 0xe0, 0xf4, 0x84, 0x10, 0x08, 0x76, 0x28, 0x5a, 0x5e, 0xde, 0xe3, 0xad,
-    0x4e, 0x56, 0xcc, 0x22, 0x85, 0x5f, 0xa9, 0xca, 0xa5, 0x4e, 0xc6, 0x82, 0xf0, 0xbb, 0x5a, 0x82,
-    0xbf, 0x32, 0x54, 0xd2, 0x8a, 0x12, 0x05, 0x6f, 0xd8, 0xd1, 0xc0, 0x60, 0x00, 0x41, 0x03, 0xf4,
-    0xa8, 0xd6, 0xee, 0x9e, 0x5a, 0x75, 0xea, 0x76, 0x88, 0xf1, 0x2d, 0x8d, 0x63, 0xfc, 0x14, 0xe9,
-    0x7b, 0xeb, 0x0b, 0x49, 0xa4, 0x8b, 0x86, 0xf4, 0x97, 0x61, 0x22, 0x29, 0x32, 0x23, 0x80, 0x2e,
-    0x59, 0x44, 0xea, 0xf5, 0x56, 0x18, 0x51, 0xc0, 0x60, 0xe1, 0x83, 0x66, 0x1f, 0x1f, 0xa1, 0x13,
-    0x64, 0xae, 0xbc, 0x59, 0xd0, 0xc4, 0x38, 0x70, 0x3f, 0xb5, 0xb5, 0xa2, 0xdc, 0x89, 0x77, 0x70,
-    0x5a, 0x86, 0xb7, 0xde, 0x67, 0x3c, 0x22, 0x83, 0xc8, 0x62, 0x77, 0xb6, 0x5d, 0x7e, 0x41, 0x99,
-    0x70, 0x7d, 0xc3, 0x27, 0x90, 0x7f, 0x3c, 0xc6, 0x90, 0xc7, 0xb9, 0x32, 0xd8, 0x01, 0xdf, 0x02,
-    0x20, 0x27, 0x8f, 0x7b, 0x76, 0xb3, 0x0d, 0xfa, 0x93, 0x46, 0x6f, 0xde, 0x23, 0x12, 0xb9, 0x4e,
-    0xb8, 0x36, 0x8e, 0x9c, 0x3a, 0x2f, 0xd4, 0x2b, 0x54, 0xab, 0x63, 0xe6, 0xf1, 0x62, 0x8c, 0xd1,
-    0x1e, 0x87, 0x48, 0x2d, 0x83, 0x93, 0xfe, 0xac, 0x29, 0x82, 0xc2, 0x3c, 0x4f, 0xd0, 0x19, 0xe7,
-    0xa3, 0x99, 0x8d, 0x7d, 0x64, 0x15, 0xde, 0x64, 0xdb, 0xc1, 0x73, 0xe0, 0x78, 0x24, 0x23, 0x9e,
-    0x8a, 0xfb, 0xe2, 0xea, 0xd5, 0x0f, 0x93, 0x51, 0x62, 0x38, 0x41, 0x3e, 0x40, 0xa4, 0x2f, 0x7e,
-    0xb8, 0x11, 0x5c, 0xd3, 0x46, 0x0a, 0x74, 0xcb, 0xb7, 0xd0, 0x15, 0x96, 0xf1, 0xe7, 0x48, 0x32,
-    0x05, 0x69, 0xa8, 0xda, 0x56, 0xee, 0x9f, 0x6a, 0x95, 0xe6, 0x0d, 0xd2, 0x2c, 0xc1, 0xc2, 0xa5,
-    0xf4, 0xd8, 0xc6, 0xd7, 0xf6, 0x1f, 0x6f, 0x88, 0x78, 0xab, 0xd4, 0x1f, 0x53, 0xa0, 0xa1, 0x3b,
-    0x03, 0xb1, 0x4a, 0xcd, 0xbe, 0x25, 0x1c, 0x6f, 0xb4, 0x30, 0x41, 0xb5, 0x4a, 0xaa, 0xa5, 0x38,
-    0x91, 0x77, 0xce, 0x48, 0xc8, 0xe1, 0x34, 0xc2, 0x85, 0xdc, 0x9b, 0x24, 0x6f, 0x63, 0x12, 0xaa,
-    0x97, 0xaf, 0x9e, 0x37, 0x1e, 0xaf, 0x94, 0x2c, 0x5c, 0x43, 0xa6, 0x93, 0x17, 0x7d, 0x1c, 0x77,
-    0x18, 0x0f, 0xb5, 0xf2, 0x66, 0x4a, 0xaf, 0xd9, 0x92, 0xfb, 0x47, 0xfc, 0x1f, 0x7a, 0xdb, 0x77,
-    0x0b, 0x11, 0x61, 0xe0, 0x85, 0x12, 0x96, 0xf7, 0x55, 0x09, 0x90, 0xa2, 0x77, 0xdb, 0x9c, 0x9c,
-    0x8b, 0x04, 0x55, 0x45, 0x39, 0x64, 0x4e, 0x46, 0x5f, 0xa2, 0x98, 0xbc, 0xd6, 0x1b, 0x5d, 0x5f,
-    0x67, 0x21, 0xc7, 0x12, 0xe5, 0xe6, 0x13, 0x93, 0x7b, 0x83, 0xd8, 0x5b, 0xcc, 0x2e, 0x77, 0xce,
-    0x56, 0x97, 0xdc, 0x96, 0x70, 0xcc, 0x47, 0x1a, 0x79, 0x1f, 0xb0, 0x6a, 0xe4, 0x7c, 0xd6, 0xa7,
-    0x28, 0x72, 0x7a, 0x1e, 0xac, 0x54, 0xc0, 0x5d, 0x8c, 0xa1, 0x00, 0xf4, 0x2e, 0x86, 0x28, 0x3a,
-    0x82, 0xce, 0xbc, 0xf0, 0xfd, 0x2d, 0xb1, 0xc4, 0x9f, 0x6d, 0x6b, 0xba, 0x9e, 0x9b, 0xec, 0xb5,
-    0x38, 0xe2, 0xe9, 0xf8, 0x57, 0x74, 0xa6, 0x9b, 0x7b, 0xec, 0xe2, 0x90, 0x0d, 0xec, 0x3e, 0xf0,
-    0x1b, 0x2b, 0x38, 0x9c, 0x82, 0xd9, 0xd1, 0xbe, 0xe1, 0x08, 0x7f, 0x02, 0x54, 0xbc, 0x0b, 0x90,
-    0x14, 0x57, 0xa2, 0x4e, 0x56, 0xbe, 0x1b, 0xdf, 0x48, 0x9c, 0x17, 0xa1, 0x70, 0xa2, 0x8c, 0x74,
-    0x74, 0x24, 0xbf, 0xe0, 0x46, 0xb7, 0xdc, 0xa4, 0xb5, 0x66, 0x44, 0xb3, 0x53, 0x0e, 0xa4, 0xe5,
-    0xb4, 0x83, 0x06, 0x1f, 0x78, 0x2a, 0xe2, 0x16, 0x5c, 0x79, 0x00, 0xab, 0x0a, 0x08, 0x57, 0xe5,
-    0xbc, 0x2c, 0x9b, 0x91, 0x19, 0xf6, 0x1d, 0x31, 0x25, 0xbb, 0x9b, 0xb5, 0x89, 0x32, 0xb7, 0x53,
-    0xb9, 0x49, 0xa4, 0x55, 0xc5, 0x9d, 0x2b, 0xa1, 0xeb, 0xd5, 0xec, 0x02, 0x40, 0x14, 0x44, 0x35,
-    0xc1, 0x65, 0xd7, 0xae, 0x4c, 0xf8, 0x16, 0xf5, 0x70, 0x62, 0x28, 0xcd, 0x58, 0x71, 0x01, 0xcb,
-    0x85, 0x09, 0x24, 0x91, 0xf0, 0x18, 0xfa, 0xc6, 0x37, 0x07, 0xde, 0xe7, 0x43, 0xed, 0x3a, 0xeb,
-    0x98, 0x97, 0xf4, 0xc5, 0xf3, 0x60, 0xa5, 0x83, 0x60, 0x1a, 0xf1, 0x81, 0x03, 0x06, 0x2c, 0x1f,
-    0x38, 0x71, 0x61, 0x30, 0x21, 0x8a, 0x49, 0xb4, 0x2a, 0xa6, 0x03, 0x0e, 0x1d, 0x1d, 0x79, 0xc0,
-    0xe5, 0x5d, 0xd1, 0x82, 0x37, 0x1b, 0x70, 0x3a, 0xd2, 0x65, 0x48, 0x59, 0xc0, 0xa2, 0xf3, 0x8b,
-    0x82, 0x24, 0xf5, 0xfe, 0x2a, 0xdd, 0x8d, 0x3a, 0xe9, 0xe5, 0x1c, 0x92, 0xc7, 0x6a, 0x43, 0xde,
-    0x78, 0xb3, 0x33, 0xa0, 0x9a, 0x78, 0x4a, 0x54, 0xba, 0x01, 0x84, 0xd2, 0xb2, 0xa4, 0x6f, 0x28,
-    0x21, 0xf2, 0xc4, 0x21, 0x9f, 0x18, 0x72, 0x16, 0x46, 0x6d, 0xfa, 0xf2, 0xa7, 0x7c, 0x99, 0xa4,
-    0x2c, 0x93, 0x4d, 0xba, 0xdd, 0xa7, 0x59, 0x50, 0x0b, 0x34, 0x48, 0x63, 0x6f, 0x0d, 0x4b, 0x81,
-    0x22, 0x3b, 0x24, 0x9b, 0x37, 0x4b, 0xd3, 0x94, 0x23, 0xc0, 0x71, 0x39, 0x91, 0xd0, 0x06, 0x2e,
-    0xd2, 0xae, 0x7c, 0xfd, 0xfd, 0x4d, 0x4a, 0x95, 0x0a, 0xd0, 0x84, 0xbf, 0x8c, 0x5c, 0xe4, 0xc1,
-    0x10, 0xfa, 0xe6, 0x26, 0x18, 0xea, 0x75, 0x3e, 0x7d, 0x0b, 0xc5, 0x9d, 0xbb, 0x14, 0x3c, 0xa5,
-    0xe3, 0x19, 0x2b, 0xdd, 0xcd, 0xbb, 0xb4, 0x48, 0x9e, 0x8c, 0xa5, 0xf2, 0xcb, 0x19, 0xa9, 0x8f,
-    0x0c, 0x0b, 0x36, 0x73, 0xd7, 0x78, 0x2b, 0x64, 0xc9, 0x8c, 0x4a, 0xb2, 0x8f, 0xe9, 0xd9, 0x51,
-    0xb1, 0x84, 0x49, 0xd1, 0xec, 0x73, 0xa6, 0xb1, 0x41, 0x70, 0xa0, 0xd3, 0xea, 0xb4, 0xf8, 0x50,
-    0x57, 0xb6, 0x69, 0xda, 0x4a, 0xe6, 0xe0, 0x73, 0xba, 0xdc, 0x98, 0xc4, 0x0e, 0xab, 0xc7, 0xbd,
-    0x8a, 0xf6, 0xe3, 0xc1, 0x0b, 0xc3, 0x5b, 0x48, 0xa1, 0x79, 0xab, 0xae, 0x9b, 0x17, 0xbd, 0x56,
-    0x3b, 0x0c, 0x38, 0xbd, 0xed, 0x86, 0x95, 0x05, 0xf4, 0x32, 0xf9, 0xdc, 0x87, 0x4f, 0x78, 0x9a,
-    0xb0, 0x6f, 0x2b, 0x76, 0xd2, 0xf4, 0x1f, 0x65, 0xf8, 0xd0, 0x9f, 0x8d, 0x3c, 0xb2, 0x53, 0x8c,
-    0xca, 0xa0, 0x2b, 0x6d, 0xfb, 0x65, 0x96, 0xde, 0x7c, 0x64, 0xbb, 0xf7, 0x7e, 0x3d, 0x8d, 0xfb,
-    0xd1, 0x2d, 0xf1, 0xc3, 0x1a, 0x66, 0x8a, 0x8f, 0x5e, 0x89, 0x37, 0x19, 0xe7, 0x9b, 0xee, 0xf0,
-    0xc1, 0xe7, 0xac, 0xe5, 0x15, 0x2f, 0x6d, 0xfb, 0x57, 0xca, 0x78, 0xd0, 0xaa, 0x19, 0xc0, 0x80,
-    0x67, 0xc9, 0x2f, 0x81, 0x26, 0x7f, 0xbe, 0xdf, 0xde, 0x50, 0x00, 0x2b, 0x1b, 0x17, 0x2a, 0x5d,
-    0x54, 0xfa, 0x46, 0xc1, 0x3f, 0xb7, 0x2f, 0xc8, 0x31, 0xf4, 0x58, 0x43, 0x36, 0xae, 0x91, 0xc4,
-    0x46, 0xc4, 0x93, 0xaf, 0x05, 0x9e, 0xa3, 0xba, 0x0c, 0x07, 0x2f, 0x0c, 0xeb, 0x2f, 0x4d, 0x4a,
-    0x90, 0xd8, 0xc0, 0x51, 0x0f, 0xa5, 0x97, 0x09, 0x16, 0xfa, 0x24, 0xc3, 0x99, 0xe6, 0x1c, 0x9e,
-    0xed, 0x03, 0x76, 0x57, 0xce, 0x29, 0x73, 0x54, 0x19, 0x5a, 0x20, 0x65, 0x52, 0xa6, 0xfa, 0x97,
-    0x00, 0x52, 0x23, 0xbb, 0x74, 0x9b, 0x6e, 0xf3, 0x25, 0x94, 0x9c, 0x66, 0x0f, 0x8b, 0x67, 0x62,
-    0x1a, 0x13, 0xdb, 0xd5, 0xdb, 0xdb, 0xb9, 0x11, 0x02, 0x13, 0x63, 0xe4, 0x6e, 0x41, 0x8a, 0xa0,
-    0xc2, 0x52, 0x71, 0x66, 0xde, 0x3b, 0x3d, 0x6c, 0xd3, 0xaa, 0x5f, 0x78, 0x3b, 0xf2, 0x7b, 0xad,
-    0x71, 0x48, 0x2e, 0x06, 0x90, 0x4c, 0x76, 0x89, 0x99, 0xb5, 0x91, 0x61, 0x29, 0x74, 0x8d, 0x90,
-    0xd6, 0x1b, 0x60, 0x11, 0xf6, 0xc0, 0xa0, 0xc7, 0x3c, 0x1e, 0xf6, 0xec, 0x80, 0x91, 0xe8, 0x6d,
-    0x46, 0xe0, 0x2c, 0xda, 0x25, 0xdb, 0x18, 0xdb, 0xb1, 0xbe, 0x7c, 0x87, 0x14, 0xa9, 0xa3, 0x46,
-    0xdb, 0x6e, 0xeb, 0x5d, 0x98, 0xd2, 0x4c, 0xfa, 0x09, 0x13, 0x71, 0xee, 0x51, 0xae, 0x5d, 0x57,
-    0x79, 0x80, 0x02, 0x43, 0x7b, 0xf7, 0x92, 0x3c, 0xf9, 0x03, 0x07, 0x4f, 0x6a, 0x53, 0x9e, 0x35,
-    0x2d, 0x40, 0x29, 0x7d, 0x44, 0x39, 0xd2, 0xb4, 0x48, 0xa7, 0x4f, 0xa5, 0x90, 0x9d, 0xb7, 0x4e,
-    0xa0, 0xb5, 0xb7, 0x92, 0x0f, 0x1c, 0x18, 0xdd, 0x4c, 0xff, 0xd4, 0xf9, 0x53, 0xd1, 0x82, 0x38,
-    0xc5, 0xcc, 0xe2, 0x36, 0x2d, 0xf7, 0x18, 0x07, 0xda, 0x5b, 0xf6, 0xef, 0xe5, 0xcb, 0xaa, 0x90,
-    0xd2, 0x84, 0x25, 0xbc, 0x4f, 0x4a, 0x3b, 0x10, 0x0f, 0x81, 0x92, 0xe4, 0xda, 0x84, 0xad, 0x8b,
-    0xbb, 0x94, 0xc5, 0xf4, 0x4c, 0x25, 0xd2, 0x08, 0x97, 0xe0, 0x98, 0x0b, 0xbe, 0xe7, 0xaa, 0x40,
-    0x06, 0xfa, 0xe4, 0x79, 0xab, 0x76, 0x1f, 0xb6, 0x7f, 0x72, 0x48, 0x5c, 0x27, 0x8b, 0xab, 0xef,
-    0x13, 0xe1, 0x0c, 0xb1, 0x79, 0x7b, 0x6d, 0x8f, 0x0b, 0x90, 0x22, 0x7f, 0x2f, 0xa5, 0x71, 0xca,
-    0xb7, 0xc0, 0xbb, 0x31, 0x23, 0x03, 0x44, 0xd2, 0x51, 0x74, 0x08, 0x85, 0xad, 0x1e, 0xfb, 0xc3,
-    0xa2, 0xa1, 0xe1, 0x3e, 0x14, 0x0e, 0xf2, 0x0a, 0xb3, 0x9d, 0x74, 0x14, 0x50, 0xff, 0xfe, 0x95,
-    0xed, 0x02, 0x76, 0xe6, 0xf7, 0x0b, 0x32, 0x4e, 0x40, 0xb0, 0xd2, 0x67, 0x74, 0x92, 0x51, 0x1e,
-    0x54, 0x6f, 0x30, 0x6f, 0x58, 0x6a, 0xbb, 0x8a, 0x6c, 0xa6, 0x6d, 0x92, 0xe3, 0x3e, 0x0c, 0x33,
-    0x7c, 0xfc, 0x87, 0x12, 0xb8, 0x61, 0x70, 0xa6, 0x6c, 0x28, 0xad, 0xa2, 0x55, 0x81, 0x58, 0xd5,
-    0xc7, 0xb6, 0xd1, 0xdf, 0x47, 0x5d, 0x07, 0x1f, 0xed, 0xfd, 0x4e, 0x0f, 0x3c, 0xe3, 0x9d, 0xae,
-    0xeb, 0xf6, 0x3a, 0xb6, 0x0e, 0x5e, 0xc9, 0x9f, 0xc9, 0x5e, 0xbc, 0x37, 0xcf, 0x81, 0x04, 0x60,
-    0x26, 0xe4, 0x8b, 0x90, 0xdb, 0xf1, 0xc2, 0x66, 0x87, 0xd2, 0x29, 0x72, 0x01, 0xa6, 0xcd, 0x01,
-    0xdf, 0x9a, 0xfb, 0x4d, 0x62, 0xcb, 0x4d, 0x59, 0x18, 0xd2, 0x3f, 0x79, 0xf7, 0xaf, 0xda, 0x10,
-    0x66, 0xb6, 0x3e, 0x7e, 0x7b, 0xcb, 0xb6, 0xa0, 0xd7, 0xa8, 0x5b, 0xe7, 0x8e, 0xa0, 0x24, 0xf4,
-    0x55, 0x43, 0x00, 0xda, 0x86, 0x50, 0xec, 0x0a, 0x04, 0x27, 0xb1, 0x98, 0x40, 0x5c, 0xb1, 0xa5,
-    0x18, 0x87, 0xa9, 0x88, 0x87, 0x85, 0xe0, 0x89, 0xd5, 0xe2, 0x24, 0xef, 0x65, 0xac, 0x2c, 0xd1,
-    0x3a, 0xe7, 0x67, 0xee, 0xc4, 0xd6, 0x3a, 0x76, 0x2a, 0x55, 0x03, 0x5b, 0x0b, 0xde, 0x5e, 0x38,
-    0x5a, 0xb7, 0x7d, 0xb3, 0x82, 0x39, 0x5c, 0x98, 0xc3, 0x2f, 0x8d, 0x53, 0xa0, 0x41, 0x7d, 0xa8,
-    0x83, 0x47, 0x4a, 0xb0, 0x42, 0xc8, 0xeb, 0xf4, 0xfb, 0x9e, 0xe9, 0x82, 0xe3, 0xf6, 0x4d, 0x16,
-    0xe4, 0xd9, 0xd1, 0xca, 0x56, 0xae, 0x09, 0xe7, 0xcf, 0xaa, 0x25, 0x15, 0x91, 0xce, 0x15, 0xa9,
-    0x87, 0x50, 0x80, 0x38, 0xcd, 0x6a, 0x64, 0x4d, 0xb5, 0x55, 0x63, 0xc7, 0x9c, 0xb0, 0x52, 0xb0,
-    0x3d, 0x5a, 0x8b, 0x65, 0x19, 0x33, 0x43, 0xa2, 0x97, 0x20, 0xad, 0x79, 0xa6, 0xe8, 0x82, 0x06,
-    0x39, 0x45, 0x8b, 0x22, 0x35, 0x28, 0x72, 0x45, 0xc8, 0xca, 0xa4, 0xc3, 0x10, 0x20, 0x22, 0x83,
-    0x8f, 0x39, 0x49, 0x83, 0x72, 0x6d, 0xf4, 0x56, 0xfd, 0x55, 0x43, 0x60, 0x3d, 0x75, 0xa3, 0x4e,
-    0x14, 0x5b, 0xb8, 0xff, 0x4f, 0x17, 0xd9, 0x93, 0xd7, 0xc0, 0xaf, 0x5c, 0xb9, 0x65, 0xa5, 0x86,
-    0x8a, 0x9a, 0x35, 0x5d, 0x95, 0x30, 0x21, 0xc3, 0x38, 0x82, 0x05, 0x0b, 0xb8, 0x02, 0x20, 0xf1,
-    0x8a, 0x37, 0x15, 0x0a, 0xc2, 0x46, 0x8a, 0x16, 0x6f, 0x0d, 0x3a, 0x5b, 0x12, 0x6e, 0xee, 0xbf,
-    0xaf, 0xfb, 0x04, 0xe5, 0x34, 0xcc, 0x44, 0x2c, 0x3c, 0x22, 0x7c, 0x4f, 0xd7, 0xad, 0xe1, 0xe2,
-    0x12, 0xe6, 0xda, 0x69, 0x6f, 0x9c, 0xc8, 0x1f, 0xb9, 0x7e, 0x42, 0x20, 0x7d, 0x21, 0x0a, 0x0f,
-    0xb6, 0x16, 0x59, 0x01, 0x91, 0x16, 0xfe, 0xa4, 0x7b, 0xb6, 0x36, 0x43, 0x72, 0xbf, 0x33, 0x71,
-    0xb8, 0x5d, 0x8a, 0x4f, 0x91, 0xe0, 0x79, 0xb5, 0x40, 0xda, 0x9f, 0x02, 0xbe, 0x35, 0x60, 0xc0,
-    0xef, 0xc9, 0x2f, 0x42, 0xd3, 0xcd, 0xef, 0xde, 0x10, 0x71, 0x14, 0x8d, 0x86, 0x29, 0xcd, 0x31,
-    0x39, 0x90, 0xe0, 0x2d, 0x28, 0x97, 0xe4, 0xb3, 0xe5, 0xd7, 0x61, 0xe3, 0x2b, 0x21, 0xe7, 0xc5,
-    0x4b, 0xd6, 0xe9, 0xdb, 0x51, 0x03, 0xa2, 0x0e, 0x7c, 0x7e, 0x95, 0xa8, 0xf6, 0x8b, 0x95, 0x8f,
-    0x6e, 0xfd, 0x54, 0xfd, 0x28, 0x71, 0x4a, 0xfc, 0x75, 0x54, 0x19, 0x8c, 0xd3, 0x8a, 0x9b, 0x94,
-    0xcd, 0x33, 0x60, 0x80, 0x7a, 0xd4, 0x68, 0x38, 0x29, 0x0e, 0xf0, 0xc1, 0xeb, 0xac, 0xaf, 0xb1,
-    0xe6, 0xce, 0xbd, 0xd1, 0xfd, 0xbc, 0x23, 0x85, 0x8d, 0x97, 0xf4, 0xc9, 0xf9, 0xbd, 0x69, 0xd2,
-    0x0c, 0x60, 0x12, 0x05, 0x82, 0x6e, 0x5a, 0x6e, 0xfd, 0x55, 0xcc, 0xd8, 0xe6, 0xed, 0x54, 0xde,
-    0xdb, 0xcc, 0xf9, 0x85, 0x0b, 0xf6, 0x42, 0x39, 0x76, 0xde, 0xab, 0x08, 0xd7, 0x65, 0xe8, 0x19,
-    0xbd, 0x72, 0xec, 0x53, 0x7d, 0xc8, 0xa6, 0x00, 0xb3, 0x3e, 0x5b, 0x7d, 0xa0, 0x7b, 0xaa, 0x6e,
-    0xc5, 0x45, 0x12, 0xe3, 0xac, 0x02, 0x1c, 0x01, 0xb8, 0x2b, 0x2b, 0xd6, 0xf8, 0x2e, 0x96, 0x3a,
-    0x5e, 0x6e, 0xda, 0xf9, 0x6c, 0xac, 0x36, 0xf8, 0xcd, 0xef, 0x05, 0x8d, 0x8f, 0xe7, 0x6c, 0x39,
-    0x24, 0xb3, 0x8d, 0xed, 0xd1, 0x70, 0xa6, 0x20, 0x1e, 0x2b, 0x1d, 0x2c, 0x40, 0x16, 0xd6, 0x23,
-    0xb0, 0x99, 0xaa, 0x74, 0x31, 0xbe, 0xdc, 0x28, 0xd4, 0x64, 0xfa, 0xd2, 0x8d, 0xdb, 0xa1, 0x32,
-    0xaf, 0xdc, 0xb1, 0x71, 0xb3, 0x4d, 0x0a, 0x3a, 0x3a, 0x53, 0x11, 0x8c, 0x11, 0x8c, 0xe7, 0xd5,
-    0xdb, 0xdd, 0x67, 0xb8, 0x1a, 0x39, 0x0e, 0xf6, 0x69, 0xd8, 0x1b, 0xcb, 0xe5, 0xc5, 0xa6, 0x3d,
-    0x3b, 0xfe, 0xe1, 0x05, 0x48, 0x83, 0x67, 0x7f, 0xf1, 0x9c, 0x70, 0x7e, 0x70, 0x5c, 0xc4, 0xd3,
-    0x78, 0x8b, 0x80, 0x87, 0xf5, 0x94, 0x2d, 0x0a, 0xbf, 0x82, 0x13, 0xad, 0x36, 0x10, 0x0a, 0x60,
-    0xaa, 0xc4, 0xa3, 0xcd, 0xf1, 0xa9, 0xa1, 0x49, 0xf5, 0x41, 0x14, 0xd6, 0xd1, 0xa7, 0xe0, 0x50,
-    0xce, 0xe2, 0x76, 0xbe, 0x09, 0xc4, 0x3a, 0x68, 0xc5, 0x8a, 0x1f, 0x8e, 0xd0, 0x4d, 0x90, 0x92,
-    0xc8, 0x3b, 0x63, 0xae, 0xc2, 0xfe, 0x77, 0x8c, 0x4b, 0xa6, 0x6c, 0x04, 0xff, 0x62, 0xe1, 0x32,
-    0x04, 0xb8, 0xfd, 0xb3, 0x34, 0x47, 0x85, 0x32, 0x6b, 0x50, 0xce, 0xcf, 0x4f, 0x0f, 0x72, 0x41,
-    0x09, 0x7f, 0xde, 0x5c, 0xe7, 0x08, 0xdf, 0x63, 0x09, 0x89, 0xd1, 0x23, 0xb6, 0xe3, 0xbc, 0xff,
-    0xe4, 0x88, 0x61, 0x67, 0x8e, 0x4a, 0xb6, 0x86, 0x08, 0x33, 0xad, 0xac, 0x9f, 0xf3, 0x37, 0xbd,
-    0xcc, 0xd6, 0x0e, 0x3e, 0x4e, 0x4f, 0xc9, 0xff, 0xc8, 0xf6, 0xd8, 0x2a, 0x43, 0x3d, 0x79, 0xf2,
-    0x5d, 0x87, 0x33, 0x1d, 0xf4, 0xfd, 0x80, 0x2c, 0x4f, 0xc4, 0xa5, 0x3a, 0x2f, 0x51, 0xb1, 0x19,
-    0xf8, 0xed, 0xe0, 0xc9, 0x6c, 0x82, 0x2d, 0x43, 0xdd, 0x71, 0x27, 0x32, 0x8b, 0x51, 0x3d, 0xbc,
-    0x83, 0x6b, 0xa8, 0xda, 0x2b, 0xbb, 0xb0, 0xba, 0x5b, 0x30, 0x4f, 0x44, 0xad, 0x99, 0xb7, 0x47,
-    0x35, 0x6f, 0x1f, 0xbe, 0x5f, 0x24, 0x4e, 0xba, 0x92, 0x89, 0xc8, 0xb6, 0x45, 0x12, 0x19, 0xee,
-    0xe7, 0xd8, 0x74, 0xb3, 0x33, 0x41, 0x39, 0x26, 0x4c, 0xc3, 0xb0, 0x22, 0x1e, 0xee, 0xd0, 0xfb,
-    0xc6, 0xa3, 0x7d, 0xaa, 0x7d, 0xa1, 0x51, 0x98, 0xe8, 0xcc, 0x58, 0x39, 0x22, 0x1e, 0xe7, 0xf0,
-    0xb4, 0xd3, 0x7c, 0xa4, 0xa6, 0x10, 0xba, 0x2f, 0x41, 0xd6, 0x17, 0x95, 0x76, 0xe1, 0xbb, 0xec,
-    0x43, 0x99, 0x87, 0xd4, 0x13, 0x9b, 0x85, 0x4f, 0x44, 0x32, 0x5a, 0xe0, 0x9a, 0xee, 0x29, 0x15,
-    0x45, 0x05, 0xf7, 0xe4, 0xc9, 0x4d, 0x6b, 0x45, 0xad, 0x1f, 0x13, 0x06, 0x29, 0xb4, 0x2b, 0x74,
-    0x83, 0xe1, 0xdb, 0x3d, 0x99, 0x28, 0xbd, 0x4f, 0xfb, 0x0d, 0x03, 0x4d, 0x79, 0x09, 0x7f, 0x12,
-    0x91, 0xe9, 0x69, 0x40, 0x84, 0xc7, 0x10, 0xd5, 0xce, 0xae, 0xbb, 0xa5, 0xb2, 0x35, 0xdb, 0x24,
-    0xa4, 0x0e, 0xe4, 0x6f, 0x80, 0xb4, 0xb6, 0x94, 0xf7, 0x51, 0x5b, 0x0c, 0x5b, 0xae, 0x3f, 0xa5,
-    0x17, 0xe0, 0x9c, 0xd1, 0x05, 0x31, 0x05, 0xc4, 0x14, 0xdd, 0xde, 0x6f, 0x39, 0xa1, 0x9a, 0x46,
-    0x3d, 0x68, 0x3c, 0xfe, 0x04, 0x8e, 0xc2, 0x11, 0x9d, 0xc2, 0x3a, 0xaa, 0xcf, 0x22, 0x26, 0x54,
-    0xcb, 0x8d, 0x47, 0x61, 0x9e, 0xf4, 0x2e, 0xe7, 0x3c, 0xe6, 0xf0, 0xd6, 0x1a, 0xe5, 0x18, 0xbd,
-    0x9f, 0xa4, 0x3e, 0x47, 0xdb, 0x85, 0xe0, 0x8c, 0xbf, 0xdd, 0x19, 0x91, 0x81, 0x80, 0x63, 0x44,
-    0x0a, 0x96, 0xae, 0xde, 0x1f, 0xbc, 0xdb, 0x15, 0x89, 0x71, 0x60, 0x23, 0xc4, 0x0a, 0x96, 0xae,
-    0xbf, 0xfc, 0x7a, 0x76, 0x4f, 0x1c, 0x9b, 0xb4, 0xcb, 0x14, 0x8b, 0x94, 0x8b, 0x94, 0xaa, 0xd6,
-    0x0f, 0x7d, 0x78, 0x72, 0x47, 0x0c, 0x9a, 0x97, 0xac, 0xbb, 0xf4, 0x6a, 0x56, 0x0f, 0x7d, 0x59,
-    0x30, 0xc3, 0x04, 0x6b, 0x54, 0x0b, 0x75, 0x49, 0x10, 0xa2, 0xa7, 0xad, 0xd8, 0x13, 0x85, 0x88,
-    0x73, 0x45, 0xe9, 0x31, 0xc1, 0x00, 0x82, 0x67, 0x4c, 0xfb, 0x74, 0x6a, 0x56, 0x2e, 0xbf, 0xdd,
-    0x19, 0x91, 0x81, 0x80, 0x82, 0x86, 0x6f, 0x5c, 0x3a, 0xf6, 0x4f, 0x1c, 0xba, 0xf6, 0x4f, 0xfd,
-    0x59, 0x11, 0x81, 0x61, 0x21, 0xa1, 0xc0, 0x02, 0x86, 0x6f, 0x3d, 0xf8, 0x53, 0x24, 0xca, 0xf7,
-    0x6c, 0x5a, 0x36, 0xcf, 0xfd, 0x59, 0x11, 0xa0, 0xa3, 0xc4, 0x0a, 0x96, 0x8f, 0x9c, 0xba, 0xd7,
-    0x0d, 0x98, 0xb2, 0xc7, 0xed, 0x58, 0x32, 0xc7, 0xed, 0x39, 0xf0, 0x43, 0x04, 0x6b, 0x54, 0x2a,
-    0xb7, 0xec, 0x5a, 0x36, 0xee, 0x3f, 0xfc, 0x7a, 0x76, 0x6e, 0x5e, 0x1f, 0x9d, 0x99, 0x91, 0x81,
-    0x80, 0x82, 0x86, 0x6f, 0x5c, 0x1b, 0x95, 0x89, 0x90, 0xa2, 0xa7, 0xcc, 0x1a, 0x97, 0xac, 0xbb,
-    0xf4, 0x6a, 0x37, 0xec, 0x3b, 0xf4, 0x6a, 0x37, 0xec, 0x3b, 0xf4, 0x4b, 0xf5, 0x49, 0xf1, 0x60,
-    0x23, 0xa5, 0xa9, 0xb1, 0xc1, 0xe1, 0x21, 0xa1, 0xa1, 0xc0, 0xe3, 0x25, 0xa9, 0xb1, 0xe0, 0x42,
-    0xe7, 0x4c, 0xfb, 0x74, 0x6a, 0x37, 0xec, 0x5a, 0x17, 0xac, 0xbb, 0xd5, 0x28, 0xd2, 0x07, 0x6d,
-    0x58, 0x13, 0x85, 0x69, 0x31, 0xe0, 0x42, 0xe7, 0x4c, 0xfb, 0x55, 0x28, 0xd2, 0x07, 0x8c, 0x7b,
-    0x74, 0x4b, 0x14, 0xaa, 0xd6, 0x2e, 0xde, 0x3e, 0xfe, 0x7e, 0x5f, 0x1d, 0xb8, 0xf2, 0x47, 0x0c,
-    0x7b, 0x55, 0x09, 0x90, 0x83, 0x65, 0x48, 0x12, 0x87, 0x6d, 0x39, 0xf0, 0x62, 0x46, 0x0e, 0x9e,
-    0x9f, 0xbc, 0xfa, 0x57, 0x2c, 0xbb, 0xd5, 0x09, 0x71, 0x60, 0x5b, 0x08, 0xf7, 0x2b,
-];
@@ -1,49 +1,96 @@
-use rmk::event::{ LayerChangeEvent, publish_event };
+use rmk::event::{ KeyboardEvent, LayerChangeEvent, publish_event };
 use rmk_macro::processor;
 use rmk::event::PointingSetCpiEvent;
+use rmk::keymap::KeyMap;
+use rmk::types::action::{Action, KeyAction};
+use crate::sensorconfig::clamp_cpi;
+use crate::notification::{Notification, NotificationEvent, NOTIFICATION_TTL};
+use crate::pointingstate::{PointingMode, PointingState, PointingStateEvent};
+use core::cell::RefCell;
+use core::fmt::Write;
+use rmk::heapless::String;
 
-#[processor(subscribe = [LayerChangeEvent])]
-pub struct PointingDeviceController {
-    current_layer: u8,
-}
+/// Keymap `User` action stepping through the CPI of the base layer
+pub const USER_CPI: u8 = 8;
+/// CPI steps of the base layer, the sniping layer always uses `SNIPING_CPI`
+const CPI_STEPS: [u16; 5] = [800, 1200, 1600, 2400, 3200];
+/// Index of the step the keyboard boots with, 1600
+const DEFAULT_CPI_STEP: usize = 2;
+const SNIPING_CPI: u16 = 200;
+/// CPI of the scroll layer, the scroll steps are counted in sensor counts
+const SCROLL_CPI: u16 = 800;
 
-impl Default for PointingDeviceController {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Sets the CPI and mode of the trackball for the active layer: the base layer moves the
+/// cursor, layer 1 snipes and layer 2 scrolls.
+///
+/// The CPI of the base layer is stepped with the `USER_CPI` key, which posts a notification.
+/// Switching layers doesn't, the sniping layer toggles the CPI on every press of its key.
+/// The chosen step isn't kept over a restart.
+#[processor(subscribe = [LayerChangeEvent, KeyboardEvent])]
+pub struct PointingDeviceController<
+    'a,
+    const ROW: usize,
+    const COL: usize,
+    const NUM_LAYER: usize,
+    const NUM_ENCODER: usize,
+> {
+    current_layer: u8,
+    /// Index into `CPI_STEPS` for the base layer
+    cpi_step: usize,
+    /// Last CPI and mode set, CPI 0 before the first layer change
+    current: PointingState,
+    keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>,
 }
 
-use defmt::info;
-impl PointingDeviceController {
-    pub fn new() -> Self {
+impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, const NUM_ENCODER: usize>
+    PointingDeviceController<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>
+{
+    pub fn new(keymap: &'a RefCell<KeyMap<'a, ROW, COL, NUM_LAYER, NUM_ENCODER>>) -> Self {
         Self {
             current_layer: 0,
+            cpi_step: DEFAULT_CPI_STEP,
+            current: PointingState::default(),
+            keymap,
         }
     }
 
-    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
-        info!("layer {}", event.layer);
-        if event.layer != self.current_layer {
-            self.current_layer = event.layer;
-        }
+    fn set(&mut self, cpi: u16, mode: PointingMode) {
+        publish_event(PointingSetCpiEvent { device_id: 0, cpi });
+        publish_event(PointingStateEvent(PointingState { cpi, mode }));
+        self.current = PointingState { cpi, mode };
+    }
 
-        match event.layer {
-            0 => {
-                info!("out: cpi 1600");
-                publish_event(PointingSetCpiEvent {
-                    device_id: 0,
-                    cpi: 1600,
-                });
-            }
-            1 => {
-                info!("out: cpi 200");
-                publish_event(PointingSetCpiEvent {
-                    device_id: 0,
-                    cpi: 200,
-                });
-            }
+    /// Sets the CPI and mode of the active layer, layers without a mode of their own keep
+    /// the current one
+    fn apply_layer(&mut self) {
+        match self.current_layer {
+            0 => self.set(clamp_cpi(CPI_STEPS[self.cpi_step]), PointingMode::Normal),
+            1 => self.set(clamp_cpi(SNIPING_CPI), PointingMode::Sniping),
+            2 => self.set(clamp_cpi(SCROLL_CPI), PointingMode::Scroll),
             _ => {}
         }
     }
-}
 
+    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
+        self.current_layer = event.layer;
+        self.apply_layer();
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        if !event.pressed {
+            return;
+        }
+        let action = self
+            .keymap
+            .borrow()
+            .get_action_at(event.pos, self.current_layer as usize);
+        if let KeyAction::Single(Action::User(USER_CPI)) = action {
+            self.cpi_step = (self.cpi_step + 1) % CPI_STEPS.len();
+            let cpi = clamp_cpi(CPI_STEPS[self.cpi_step]);
+            let mut text: String<16> = String::new();
+            write!(text, "CPI {}", cpi).unwrap();
+            publish_event(NotificationEvent(Notification::new(&text, NOTIFICATION_TTL)));
+            self.apply_layer();
+        }
+    }
+}
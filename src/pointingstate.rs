@@ -0,0 +1,43 @@
+use rmk_macro::event;
+
+/// What the trackball does, chosen by the layer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum PointingMode {
+    #[default]
+    Normal,
+    /// Low CPI for precise movement
+    Sniping,
+    /// Motion scrolls instead of moving the cursor
+    Scroll,
+}
+
+impl PointingMode {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => PointingMode::Sniping,
+            2 => PointingMode::Scroll,
+            _ => PointingMode::Normal,
+        }
+    }
+
+    /// Name shown on the display, 8 characters at most
+    pub const fn name(self) -> &'static str {
+        match self {
+            PointingMode::Normal => "NORMAL",
+            PointingMode::Sniping => "SNIPING",
+            PointingMode::Scroll => "SCROLL",
+        }
+    }
+}
+
+/// CPI and mode set by the `PointingDeviceController`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct PointingState {
+    /// 0 until the controller set a CPI
+    pub cpi: u16,
+    pub mode: PointingMode,
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct PointingStateEvent(pub PointingState);
@@ -0,0 +1,75 @@
+//! Scroll mode of the trackball on the central.
+//!
+//! The sensor bus hands every motion burst the driver reads to [`on_motion_burst`]. While the
+//! pointing mode is [`PointingMode::Scroll`] it takes the motion out of the burst, so the
+//! pointing processor sees none, and [`ScrollController`] sends it as wheel and pan reports.
+use crate::pointingstate::{PointingMode, PointingStateEvent};
+use crate::scrollmotion::{take_motion, ScrollAccumulator};
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use rmk::channel::KEYBOARD_REPORT_CHANNEL;
+use rmk::hid::Report;
+use rmk_macro::processor;
+use usbd_hid::descriptor::MouseReport;
+
+static SCROLLING: AtomicBool = AtomicBool::new(false);
+/// Motion taken from the bursts and not reported yet
+static SCROLL: Mutex<CriticalSectionRawMutex, RefCell<ScrollAccumulator>> =
+    Mutex::new(RefCell::new(ScrollAccumulator::new()));
+
+/// Called with the data of every motion burst read by the driver, takes the motion while
+/// scrolling
+pub fn on_motion_burst(burst: &mut [u8]) {
+    if !SCROLLING.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Some((dx, dy)) = take_motion(burst) {
+        SCROLL.lock(|scroll| scroll.borrow_mut().add(dx, dy));
+    }
+}
+
+/// Reports the motion taken while scrolling, once per sensor report at 125 Hz
+#[processor(subscribe = [PointingStateEvent], poll_interval = 8)]
+pub struct ScrollController {
+    scrolling: bool,
+}
+
+impl Default for ScrollController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScrollController {
+    pub fn new() -> Self {
+        Self { scrolling: false }
+    }
+
+    async fn on_pointing_state_event(&mut self, event: PointingStateEvent) {
+        let scrolling = event.0.mode == PointingMode::Scroll;
+        if scrolling != self.scrolling {
+            self.scrolling = scrolling;
+            SCROLLING.store(scrolling, Ordering::SeqCst);
+            // A step started before doesn't belong to the next time
+            SCROLL.lock(|scroll| scroll.borrow_mut().reset());
+        }
+    }
+
+    pub async fn poll(&mut self) {
+        let Some((wheel, pan)) = SCROLL.lock(|scroll| scroll.borrow_mut().take()) else {
+            return;
+        };
+        let mouse_report = MouseReport {
+            buttons: 0,
+            x: 0,
+            y: 0,
+            wheel,
+            pan,
+        };
+        KEYBOARD_REPORT_CHANNEL
+            .send(Report::MouseReport(mouse_report))
+            .await;
+    }
+}
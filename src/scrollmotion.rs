@@ -0,0 +1,150 @@
+//! Scrolling with the trackball.
+//!
+//! rmk's pointing processor moves the cursor and knows nothing of scrolling. While the
+//! pointing mode is [`PointingMode::Scroll`](crate::pointingstate::PointingMode::Scroll), the
+//! sensor bus takes the deltas out of each motion burst with [`take_motion`] before the driver
+//! sees them, and a [`ScrollAccumulator`] turns them into wheel and pan steps. The ball scrolls
+//! the way it would move the cursor, with the X axis inverted like the pointing processor of
+//! the central.
+use crate::motiontrace::{BURST_DELTA_LEN, MOTION_MOT};
+
+/// Sensor counts per wheel or pan step
+pub const SCROLL_COUNTS_PER_STEP: i32 = 32;
+
+/// Takes the deltas out of a motion burst read, the burst then holds no motion. `None` if it
+/// had none.
+pub fn take_motion(burst: &mut [u8]) -> Option<(i16, i16)> {
+    if burst.len() < BURST_DELTA_LEN || burst[0] & MOTION_MOT == 0 {
+        return None;
+    }
+    let dx = i16::from_le_bytes([burst[2], burst[3]]);
+    let dy = i16::from_le_bytes([burst[4], burst[5]]);
+    burst[0] &= !MOTION_MOT;
+    burst[2..BURST_DELTA_LEN].fill(0);
+    Some((dx, dy))
+}
+
+/// Collects sensor counts into wheel and pan steps, the counts short of a step carry over to
+/// the next report
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScrollAccumulator {
+    /// Counts towards a pan step, the X axis inverted
+    x: i32,
+    /// Counts towards a wheel step, up positive
+    y: i32,
+}
+
+impl ScrollAccumulator {
+    pub const fn new() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    /// Adds the deltas of a motion burst
+    pub fn add(&mut self, dx: i16, dy: i16) {
+        // Rolled up, where the cursor would go up, turns the wheel up
+        self.x = self.x.saturating_sub(dx.into());
+        self.y = self.y.saturating_sub(dy.into());
+    }
+
+    /// Takes the `(wheel, pan)` steps to report, `None` while both are short of a step
+    pub fn take(&mut self) -> Option<(i8, i8)> {
+        let wheel = Self::steps(&mut self.y);
+        let pan = Self::steps(&mut self.x);
+        (wheel != 0 || pan != 0).then_some((wheel, pan))
+    }
+
+    /// Drops the counts short of a step, when scrolling stops
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Takes the whole steps out of `counts`, at most what fits a report
+    fn steps(counts: &mut i32) -> i8 {
+        let steps = (*counts / SCROLL_COUNTS_PER_STEP).clamp(-127, 127);
+        *counts -= steps * SCROLL_COUNTS_PER_STEP;
+        steps as i8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn burst(dx: i16, dy: i16) -> [u8; 7] {
+        let [dx_lo, dx_hi] = dx.to_le_bytes();
+        let [dy_lo, dy_hi] = dy.to_le_bytes();
+        [MOTION_MOT, 0, dx_lo, dx_hi, dy_lo, dy_hi, 52]
+    }
+
+    #[test]
+    fn takes_the_motion_out_of_a_burst() {
+        let mut burst = burst(-300, 12);
+        assert_eq!(take_motion(&mut burst), Some((-300, 12)));
+        assert_eq!(burst, [0, 0, 0, 0, 0, 0, 52]);
+        assert_eq!(take_motion(&mut burst), None);
+    }
+
+    #[test]
+    fn leaves_bursts_without_motion() {
+        let mut still = [0x00, 0, 5, 0, 0, 0];
+        assert_eq!(take_motion(&mut still), None);
+        assert_eq!(still, [0x00, 0, 5, 0, 0, 0]);
+        let mut short = [MOTION_MOT, 0, 5];
+        assert_eq!(take_motion(&mut short), None);
+    }
+
+    #[test]
+    fn scrolls_once_a_step_is_moved() {
+        let mut scroll = ScrollAccumulator::new();
+        scroll.add(0, -20);
+        assert_eq!(scroll.take(), None);
+        // Rolled up: the wheel turns up
+        scroll.add(0, -20);
+        assert_eq!(scroll.take(), Some((1, 0)));
+        // 8 counts left over
+        scroll.add(0, 30);
+        assert_eq!(scroll.take(), None);
+        scroll.add(0, 20);
+        assert_eq!(scroll.take(), Some((-1, 0)));
+    }
+
+    #[test]
+    fn pans_with_the_inverted_x_axis() {
+        let mut scroll = ScrollAccumulator::new();
+        scroll.add(-64, 0);
+        assert_eq!(scroll.take(), Some((0, 2)));
+        scroll.add(40, 0);
+        assert_eq!(scroll.take(), Some((0, -1)));
+    }
+
+    #[test]
+    fn sums_the_bursts_until_taken() {
+        let mut scroll = ScrollAccumulator::new();
+        for _ in 0..5 {
+            scroll.add(-10, 10);
+        }
+        assert_eq!(scroll.take(), Some((-1, 1)));
+        assert_eq!(scroll.take(), None);
+    }
+
+    #[test]
+    fn carries_what_doesnt_fit_a_report() {
+        let mut scroll = ScrollAccumulator::new();
+        scroll.add(0, i16::MIN);
+        // 1024 steps, 127 per report
+        for _ in 0..8 {
+            assert_eq!(scroll.take(), Some((127, 0)));
+        }
+        assert_eq!(scroll.take(), Some((8, 0)));
+        assert_eq!(scroll.take(), None);
+    }
+
+    #[test]
+    fn forgets_partial_steps_on_reset() {
+        let mut scroll = ScrollAccumulator::new();
+        scroll.add(0, -20);
+        scroll.reset();
+        scroll.add(0, -20);
+        assert_eq!(scroll.take(), None);
+    }
+}
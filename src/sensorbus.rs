@@ -0,0 +1,283 @@
+//! Shares the SPI bus of the trackball sensor between rmk's `Pmw33xx` driver and our own
+//! register accesses (SROM verification, diagnostics).
+//!
+//! The driver gets a [`SensorBusDevice`] and a [`SensorBusCs`] instead of the real bus and
+//! chip select. Whoever pulls chip select low first owns the bus until it is released again,
+//! so a driver transaction is never interleaved with one of ours. The side waiting for the bus
+//! sleeps on a [`Signal`] until the other one releases it, so each side is a single task.
+use crate::activitystate::{ActivityEvent, ActivityThrottle};
+use crate::motiontrace::{has_motion, MotionTap, MOTION_BURST};
+use crate::scrollcontroller;
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicBool, Ordering};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Timer;
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::spi::{ErrorType, SpiBus};
+use rmk::event::publish_event;
+
+/// `SROM_Load_Burst` with the write bit, the first byte of the driver's SROM upload
+const SROM_LOAD_BURST_WRITE: u8 = 0x62 | 0x80;
+
+struct Inner<SPI, CS> {
+    spi: SPI,
+    cs: CS,
+    cs_low: bool,
+    /// Bytes written since chip select went low
+    written: usize,
+    /// First byte written since chip select went low
+    command: u8,
+    motion_tap: MotionTap,
+    /// Motion keeps the display awake
+    activity: ActivityThrottle,
+}
+
+impl<SPI, CS: OutputPin> Inner<SPI, CS> {
+    fn select(&mut self, low: bool) {
+        if self.cs_low != low {
+            let _ = if low {
+                self.cs.set_low()
+            } else {
+                self.cs.set_high()
+            };
+            self.cs_low = low;
+            self.written = 0;
+            self.command = 0;
+        }
+    }
+}
+
+pub struct SensorBus<SPI, CS> {
+    inner: Mutex<CriticalSectionRawMutex, Inner<SPI, CS>>,
+    /// The driver has pulled its chip select low
+    driver_selected: AtomicBool,
+    /// One of our own transactions is running
+    local_active: AtomicBool,
+    /// Cleared to stop the driver from ever touching the sensor again
+    driver_enabled: AtomicBool,
+    /// The driver's current transaction is a SROM upload
+    srom_burst: AtomicBool,
+    /// Signaled when the driver ends a SROM upload
+    srom_uploaded: Signal<CriticalSectionRawMutex, ()>,
+    /// Signaled when the driver releases the bus
+    local_wake: Signal<CriticalSectionRawMutex, ()>,
+    /// Signaled when we release the bus
+    driver_wake: Signal<CriticalSectionRawMutex, ()>,
+}
+
+impl<SPI, CS> SensorBus<SPI, CS>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+{
+    pub fn new(spi: SPI, mut cs: CS) -> Self {
+        let _ = cs.set_high();
+        Self {
+            inner: Mutex::new(Inner {
+                spi,
+                cs,
+                cs_low: false,
+                written: 0,
+                command: 0,
+                motion_tap: MotionTap::new(),
+                activity: ActivityThrottle::new(),
+            }),
+            driver_selected: AtomicBool::new(false),
+            local_active: AtomicBool::new(false),
+            driver_enabled: AtomicBool::new(true),
+            srom_burst: AtomicBool::new(false),
+            srom_uploaded: Signal::new(),
+            local_wake: Signal::new(),
+            driver_wake: Signal::new(),
+        }
+    }
+
+    /// Bus handle for the `Pmw33xx` driver
+    pub fn device(&self) -> SensorBusDevice<'_, SPI, CS> {
+        SensorBusDevice { bus: self }
+    }
+
+    /// Chip select handle for the `Pmw33xx` driver
+    pub fn cs(&self) -> SensorBusCs<'_, SPI, CS> {
+        SensorBusCs { bus: self }
+    }
+
+    /// Waits until the driver has written the SROM to the sensor
+    pub async fn srom_uploaded(&self) {
+        self.srom_uploaded.wait().await
+    }
+
+    /// Blocks every further bus access of the driver, so it stops reporting motion. The driver
+    /// waits for its turn forever.
+    pub fn disable_driver(&self) {
+        self.driver_enabled.store(false, Ordering::SeqCst);
+    }
+
+    /// Reads a sensor register, waiting until the driver is done with its transaction
+    pub async fn read_register(&self, addr: u8) -> Result<u8, SPI::Error> {
+        self.acquire().await;
+        let mut inner = self.inner.lock().await;
+        inner.select(true);
+        let mut value = [0u8];
+        let mut result = inner.spi.write(&[addr & 0x7f]).await;
+        if result.is_ok() {
+            // tSRAD
+            Timer::after_micros(160).await;
+            result = inner.spi.read(&mut value).await;
+        }
+        // tSCLK-NCS for reads
+        Timer::after_micros(1).await;
+        inner.select(false);
+        drop(inner);
+        // tSRW/tSRR
+        Timer::after_micros(20).await;
+        self.release();
+        result.map(|_| value[0])
+    }
+
+    /// Writes a sensor register, waiting until the driver is done with its transaction
+    pub async fn write_register(&self, addr: u8, value: u8) -> Result<(), SPI::Error> {
+        self.acquire().await;
+        let mut inner = self.inner.lock().await;
+        inner.select(true);
+        let result = inner.spi.write(&[addr | 0x80, value]).await;
+        // tSCLK-NCS for writes
+        Timer::after_micros(35).await;
+        inner.select(false);
+        drop(inner);
+        // tSWW/tSWR
+        Timer::after_micros(180).await;
+        self.release();
+        result
+    }
+
+    async fn acquire(&self) {
+        // A stale signal only means another look at the flags
+        while self.driver_selected.load(Ordering::SeqCst)
+            || self.local_active.load(Ordering::SeqCst)
+        {
+            self.local_wake.wait().await;
+        }
+        self.local_active.store(true, Ordering::SeqCst);
+    }
+
+    fn release(&self) {
+        self.local_active.store(false, Ordering::SeqCst);
+        self.driver_wake.signal(());
+        // The driver may have selected the sensor while we were busy
+        if self.driver_selected.load(Ordering::SeqCst) {
+            if let Ok(mut inner) = self.inner.try_lock() {
+                inner.select(true);
+            }
+        }
+    }
+
+    /// Waits until the driver may use the bus and selects the sensor for it
+    async fn driver_turn(
+        &self,
+    ) -> embassy_sync::mutex::MutexGuard<'_, CriticalSectionRawMutex, Inner<SPI, CS>> {
+        while self.local_active.load(Ordering::SeqCst)
+            || !self.driver_enabled.load(Ordering::SeqCst)
+        {
+            self.driver_wake.wait().await;
+        }
+        let mut inner = self.inner.lock().await;
+        if self.driver_selected.load(Ordering::SeqCst) {
+            inner.select(true);
+        }
+        inner
+    }
+}
+
+pub struct SensorBusDevice<'a, SPI, CS> {
+    bus: &'a SensorBus<SPI, CS>,
+}
+
+impl<SPI: SpiBus, CS: OutputPin> ErrorType for SensorBusDevice<'_, SPI, CS> {
+    type Error = SPI::Error;
+}
+
+impl<SPI: SpiBus, CS: OutputPin> SpiBus for SensorBusDevice<'_, SPI, CS> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        let mut inner = self.bus.driver_turn().await;
+        inner.spi.read(words).await?;
+        if inner.command == MOTION_BURST {
+            inner.motion_tap.on_motion_burst(words);
+            if has_motion(words) && inner.activity.pass() {
+                publish_event(ActivityEvent);
+            }
+            scrollcontroller::on_motion_burst(words);
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut inner = self.bus.driver_turn().await;
+        let result = inner.spi.write(words).await;
+        if inner.written == 0 {
+            if let Some(&command) = words.first() {
+                inner.command = command;
+                // The SROM follows in any number of writes until chip select goes high
+                if command == SROM_LOAD_BURST_WRITE {
+                    self.bus.srom_burst.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+        inner.written += words.len();
+        result
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.bus.driver_turn().await.spi.transfer(read, write).await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus
+            .driver_turn()
+            .await
+            .spi
+            .transfer_in_place(words)
+            .await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.bus.driver_turn().await.spi.flush().await
+    }
+}
+
+pub struct SensorBusCs<'a, SPI, CS> {
+    bus: &'a SensorBus<SPI, CS>,
+}
+
+impl<SPI, CS> embedded_hal::digital::ErrorType for SensorBusCs<'_, SPI, CS> {
+    type Error = Infallible;
+}
+
+impl<SPI, CS: OutputPin> OutputPin for SensorBusCs<'_, SPI, CS> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.bus.driver_selected.store(true, Ordering::SeqCst);
+        // Deferred to the next bus access if one of our transactions is running
+        if !self.bus.local_active.load(Ordering::SeqCst) {
+            if let Ok(mut inner) = self.bus.inner.try_lock() {
+                inner.select(true);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.bus.driver_selected.store(false, Ordering::SeqCst);
+        if self.bus.srom_burst.swap(false, Ordering::SeqCst) {
+            self.bus.srom_uploaded.signal(());
+        }
+        if !self.bus.local_active.load(Ordering::SeqCst) {
+            if let Ok(mut inner) = self.bus.inner.try_lock() {
+                inner.select(false);
+            }
+        }
+        self.bus.local_wake.signal(());
+        Ok(())
+    }
+}
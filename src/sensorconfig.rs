@@ -0,0 +1,20 @@
+// Sensor SROM, its CRC and the CPI limits are generated by `build.rs` from `[sensor]` in
+// keyboard.toml. The SROM image can be overridden by the `SROM_PATH` environment variable.
+include!(concat!(env!("OUT_DIR"), "/sensor_generated.rs"));
+
+#[cfg(sensor = "pmw3360")]
+pub type SensorSpec = rmk::input_device::pmw33xx::Pmw3360Spec;
+#[cfg(sensor = "pmw3389")]
+pub type SensorSpec = rmk::input_device::pmw33xx::Pmw3389Spec;
+
+/// Clamps a CPI value to the range of the selected sensor and rounds it down to a supported step
+pub const fn clamp_cpi(cpi: u16) -> u16 {
+    let cpi = if cpi < SENSOR_CPI_MIN {
+        SENSOR_CPI_MIN
+    } else if cpi > SENSOR_CPI_MAX {
+        SENSOR_CPI_MAX
+    } else {
+        cpi
+    };
+    cpi - cpi % SENSOR_CPI_STEP
+}
@@ -0,0 +1,142 @@
+use crate::notification::{Notification, NotificationEvent};
+use crate::sensorbus::SensorBus;
+use crate::sensorconfig::{SENSOR_SROM, SENSOR_SROM_CRC};
+use crate::sensorstate::{SensorDiag, SensorDiagEvent, SensorStatus, SensorStatusEvent};
+use crc::{Crc, CRC_16_IBM_3740};
+use defmt::{debug, error, info};
+use embassy_time::{with_timeout, Duration, Timer};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::spi::SpiBus;
+use rmk::event::publish_event;
+
+const REG_SQUAL: u8 = 0x07;
+const REG_RAW_DATA_SUM: u8 = 0x08;
+const REG_MAXIMUM_RAW_DATA: u8 = 0x09;
+const REG_MINIMUM_RAW_DATA: u8 = 0x0a;
+const REG_SHUTTER_LOWER: u8 = 0x0b;
+const REG_SHUTTER_UPPER: u8 = 0x0c;
+const REG_SROM_ENABLE: u8 = 0x13;
+const REG_DATA_OUT_LOWER: u8 = 0x25;
+const REG_DATA_OUT_UPPER: u8 = 0x26;
+const REG_SROM_ID: u8 = 0x2a;
+
+/// Starts the SROM CRC test when written to `SROM_Enable`
+const SROM_CRC_TEST: u8 = 0x15;
+/// `Data_Out_Upper:Data_Out_Lower` after a passed SROM CRC test
+const SROM_CRC_PASSED: u16 = 0xbeef;
+
+/// Maximum time to wait for the driver to upload the SROM
+const SROM_UPLOAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval of the image quality readout
+const DIAG_INTERVAL: Duration = Duration::from_millis(250);
+/// A failed sensor is worth more than a glance, the status page keeps showing it afterwards
+const FAILURE_NOTIFICATION_TTL: Duration = Duration::from_secs(10);
+
+pub const SROM_CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Verifies the SROM after the `Pmw33xx` driver uploaded it and shuts the driver out of the bus
+/// if the sensor is not running the firmware we built with. Afterwards the image quality
+/// registers are sampled periodically.
+pub struct SensorMonitor<'a, SPI, CS> {
+    bus: &'a SensorBus<SPI, CS>,
+}
+
+impl<'a, SPI, CS> SensorMonitor<'a, SPI, CS>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+{
+    pub fn new(bus: &'a SensorBus<SPI, CS>) -> Self {
+        Self { bus }
+    }
+
+    pub async fn run(&mut self) {
+        let status = self.verify_srom().await;
+        if status == SensorStatus::Ok {
+            info!("Sensor SROM verified");
+        } else {
+            error!("Sensor SROM check failed: {}, motion disabled", status);
+            self.bus.disable_driver();
+            publish_event(NotificationEvent(Notification::new(
+                "BALL ERROR",
+                FAILURE_NOTIFICATION_TTL,
+            )));
+        }
+        publish_event(SensorStatusEvent(status));
+        if status != SensorStatus::Ok {
+            return;
+        }
+
+        loop {
+            Timer::after(DIAG_INTERVAL).await;
+            match self.read_diag().await {
+                Ok(diag) => {
+                    debug!("Sensor diag: {}", diag);
+                    publish_event(SensorDiagEvent(diag));
+                }
+                Err(_) => error!("Reading sensor diagnostics failed"),
+            }
+        }
+    }
+
+    async fn read_diag(&mut self) -> Result<SensorDiag, SPI::Error> {
+        Ok(SensorDiag {
+            squal: self.bus.read_register(REG_SQUAL).await?,
+            raw_data_sum: self.bus.read_register(REG_RAW_DATA_SUM).await?,
+            max_raw: self.bus.read_register(REG_MAXIMUM_RAW_DATA).await?,
+            min_raw: self.bus.read_register(REG_MINIMUM_RAW_DATA).await?,
+            shutter: u16::from_le_bytes([
+                self.bus.read_register(REG_SHUTTER_LOWER).await?,
+                self.bus.read_register(REG_SHUTTER_UPPER).await?,
+            ]),
+        })
+    }
+
+    async fn verify_srom(&mut self) -> SensorStatus {
+        if SROM_CRC.checksum(SENSOR_SROM) != SENSOR_SROM_CRC {
+            return SensorStatus::BlobCorrupt;
+        }
+
+        if with_timeout(SROM_UPLOAD_TIMEOUT, self.bus.srom_uploaded())
+            .await
+            .is_err()
+        {
+            return SensorStatus::NoResponse;
+        }
+        // Let the driver finish its init sequence after the burst
+        Timer::after_millis(100).await;
+
+        let Ok(srom_id) = self.bus.read_register(REG_SROM_ID).await else {
+            return SensorStatus::NoResponse;
+        };
+        // The second byte of the blob is the firmware version reported in SROM_ID
+        if srom_id != SENSOR_SROM[1] {
+            info!("SROM_ID {:#x}, expected {:#x}", srom_id, SENSOR_SROM[1]);
+            return SensorStatus::SromIdMismatch;
+        }
+
+        if self
+            .bus
+            .write_register(REG_SROM_ENABLE, SROM_CRC_TEST)
+            .await
+            .is_err()
+        {
+            return SensorStatus::NoResponse;
+        }
+        Timer::after_millis(10).await;
+        let (Ok(lower), Ok(upper)) = (
+            self.bus.read_register(REG_DATA_OUT_LOWER).await,
+            self.bus.read_register(REG_DATA_OUT_UPPER).await,
+        ) else {
+            return SensorStatus::NoResponse;
+        };
+        let crc = u16::from_le_bytes([lower, upper]);
+        if crc != SROM_CRC_PASSED {
+            info!("SROM CRC test returned {:#x}", crc);
+            return SensorStatus::SromCrcFailed;
+        }
+
+        SensorStatus::Ok
+    }
+}
@@ -0,0 +1,48 @@
+use rmk_macro::event;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum SensorStatus {
+    /// SROM verified, motion is reported
+    Ok,
+    /// The SROM blob in flash does not match the CRC computed at build time
+    BlobCorrupt,
+    /// The sensor reports a different SROM version than the one uploaded
+    SromIdMismatch,
+    /// The sensor's own SROM CRC test failed
+    SromCrcFailed,
+    /// The driver never uploaded the SROM or the sensor didn't answer
+    NoResponse,
+}
+
+impl SensorStatus {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => SensorStatus::Ok,
+            1 => SensorStatus::BlobCorrupt,
+            2 => SensorStatus::SromIdMismatch,
+            3 => SensorStatus::SromCrcFailed,
+            _ => SensorStatus::NoResponse,
+        }
+    }
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct SensorStatusEvent(pub SensorStatus);
+
+/// Image quality registers of the sensor, used to tune lens height and ball bearings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct SensorDiag {
+    /// Number of valid features on the surface, `SQUAL`
+    pub squal: u8,
+    /// Average raw pixel value, `Raw_Data_Sum`
+    pub raw_data_sum: u8,
+    pub max_raw: u8,
+    pub min_raw: u8,
+    /// Exposure time in clock cycles, `Shutter_Upper:Shutter_Lower`
+    pub shutter: u16,
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct SensorDiagEvent(pub SensorDiag);
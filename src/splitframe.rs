@@ -0,0 +1,482 @@
+//! Framing and messages of the split link, see [`crate::splitlink`] for the transport.
+//!
+//! `SYNC | channel | length | payload | CRC-8`
+//!
+//! The rmk channel carries rmk's own bytes, the sync channel one encoded [`SyncMessage`] per
+//! frame. [`FrameParser`] finds the frames in the received bytes and skips anything between
+//! them, so a half that starts listening in the middle of a frame resyncs on the next one.
+use crate::displaycommands::DisplayCommand;
+use crate::displaysettings::DisplaySettings;
+use crate::modifierstate::Modifiers;
+use crate::notification::Notification;
+use crate::pointingstate::{PointingMode, PointingState};
+use crate::sensorstate::{SensorDiag, SensorStatus};
+use crate::typingstate::TypingSummary;
+use crate::usbstate::UsbState;
+use crc::{Crc, CRC_8_SMBUS};
+
+const FRAME_SYNC: u8 = 0xa5;
+pub const CHANNEL_RMK: u8 = 0;
+pub const CHANNEL_SYNC: u8 = 1;
+/// Sync byte, channel and length
+const HEADER_LEN: usize = 3;
+pub const MAX_PAYLOAD: usize = 64;
+pub const MAX_FRAME: usize = HEADER_LEN + MAX_PAYLOAD + 1;
+
+const FRAME_CRC: Crc<u8> = Crc::<u8>::new(&CRC_8_SMBUS);
+
+/// Frames `payload` for `channel` into `frame`, returns the frame length
+pub fn encode_frame(channel: u8, payload: &[u8], frame: &mut [u8; MAX_FRAME]) -> usize {
+    let len = HEADER_LEN + payload.len();
+    frame[..HEADER_LEN].copy_from_slice(&[FRAME_SYNC, channel, payload.len() as u8]);
+    frame[HEADER_LEN..len].copy_from_slice(payload);
+    frame[len] = FRAME_CRC.checksum(&frame[..len]);
+    len + 1
+}
+
+/// A valid frame received
+#[derive(Debug, PartialEq, Eq)]
+pub struct Frame<'a> {
+    pub channel: u8,
+    pub payload: &'a [u8],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum FrameError {
+    /// A sync byte followed by a length too long for a frame, not a frame start
+    Length,
+    /// The frame doesn't match its CRC and is dropped
+    Crc,
+}
+
+/// Collects the received bytes into frames
+pub struct FrameParser {
+    frame: [u8; MAX_FRAME],
+    len: usize,
+}
+
+impl Default for FrameParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameParser {
+    pub const fn new() -> Self {
+        Self {
+            frame: [0; MAX_FRAME],
+            len: 0,
+        }
+    }
+
+    /// Adds a received byte, returns the frame it completes or the error it reveals
+    pub fn push(&mut self, byte: u8) -> Option<Result<Frame<'_>, FrameError>> {
+        if self.len == 0 && byte != FRAME_SYNC {
+            return None;
+        }
+        self.frame[self.len] = byte;
+        self.len += 1;
+
+        if self.len < HEADER_LEN {
+            return None;
+        }
+        let payload_len = self.frame[2] as usize;
+        if payload_len > MAX_PAYLOAD {
+            self.len = 0;
+            return Some(Err(FrameError::Length));
+        }
+        if self.len < HEADER_LEN + payload_len + 1 {
+            return None;
+        }
+
+        let len = self.len;
+        self.len = 0;
+        let (data, crc) = self.frame[..len].split_at(len - 1);
+        if FRAME_CRC.checksum(data) != crc[0] {
+            return Some(Err(FrameError::Crc));
+        }
+        Some(Ok(Frame {
+            channel: data[1],
+            payload: &data[HEADER_LEN..],
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum SyncMessage {
+    Jiggle(bool),
+    SensorStatus(SensorStatus),
+    SensorDiag(SensorDiag),
+    DisplayCommand(DisplayCommand),
+    DisplaySettings(DisplaySettings),
+    TypingSummary(TypingSummary),
+    Modifiers(Modifiers),
+    Notification(Notification),
+    PointingState(PointingState),
+    UsbState(UsbState),
+    /// Sent when there was nothing else to send for a while, not published
+    Heartbeat,
+    /// A key was pressed or the ball moved
+    Activity,
+}
+
+impl SyncMessage {
+    const JIGGLE: u8 = 0;
+    const SENSOR_STATUS: u8 = 1;
+    const SENSOR_DIAG: u8 = 2;
+    const DISPLAY_COMMAND: u8 = 3;
+    const DISPLAY_SETTINGS: u8 = 4;
+    const TYPING_SUMMARY: u8 = 5;
+    const MODIFIERS: u8 = 6;
+    const NOTIFICATION: u8 = 7;
+    const POINTING_STATE: u8 = 8;
+    const HEARTBEAT: u8 = 9;
+    const USB_STATE: u8 = 10;
+    const ACTIVITY: u8 = 11;
+
+    /// Encodes the message into `buf`, returns the encoded length
+    pub fn encode(&self, buf: &mut [u8; MAX_PAYLOAD]) -> usize {
+        match *self {
+            SyncMessage::Jiggle(active) => {
+                buf[..2].copy_from_slice(&[Self::JIGGLE, active as u8]);
+                2
+            }
+            SyncMessage::SensorStatus(status) => {
+                buf[..2].copy_from_slice(&[Self::SENSOR_STATUS, status as u8]);
+                2
+            }
+            SyncMessage::SensorDiag(diag) => {
+                let [shutter_lo, shutter_hi] = diag.shutter.to_le_bytes();
+                buf[..7].copy_from_slice(&[
+                    Self::SENSOR_DIAG,
+                    diag.squal,
+                    diag.raw_data_sum,
+                    diag.max_raw,
+                    diag.min_raw,
+                    shutter_lo,
+                    shutter_hi,
+                ]);
+                7
+            }
+            SyncMessage::DisplayCommand(command) => {
+                buf[..2].copy_from_slice(&[Self::DISPLAY_COMMAND, command as u8]);
+                2
+            }
+            SyncMessage::DisplaySettings(settings) => {
+                buf[..3].copy_from_slice(&[
+                    Self::DISPLAY_SETTINGS,
+                    settings.contrast,
+                    settings.display_on as u8,
+                ]);
+                3
+            }
+            SyncMessage::TypingSummary(summary) => {
+                buf[0] = Self::TYPING_SUMMARY;
+                buf[1..5].copy_from_slice(&summary.total_presses.to_le_bytes());
+                buf[5..9].copy_from_slice(&summary.session_presses.to_le_bytes());
+                buf[9..13].copy_from_slice(&summary.session_typing_secs.to_le_bytes());
+                buf[13..17].copy_from_slice(&summary.layer_percent);
+                17
+            }
+            SyncMessage::Modifiers(modifiers) => {
+                buf[..2].copy_from_slice(&[Self::MODIFIERS, modifiers.bits()]);
+                2
+            }
+            SyncMessage::Notification(notification) => {
+                let text = notification.bytes();
+                buf[0] = Self::NOTIFICATION;
+                buf[1..3].copy_from_slice(&notification.ttl_ms.to_le_bytes());
+                buf[3..3 + text.len()].copy_from_slice(text);
+                3 + text.len()
+            }
+            SyncMessage::PointingState(state) => {
+                let [cpi_lo, cpi_hi] = state.cpi.to_le_bytes();
+                buf[..4].copy_from_slice(&[Self::POINTING_STATE, cpi_lo, cpi_hi, state.mode as u8]);
+                4
+            }
+            SyncMessage::Heartbeat => {
+                buf[0] = Self::HEARTBEAT;
+                1
+            }
+            SyncMessage::UsbState(state) => {
+                buf[..2].copy_from_slice(&[Self::USB_STATE, state as u8]);
+                2
+            }
+            SyncMessage::Activity => {
+                buf[0] = Self::ACTIVITY;
+                1
+            }
+        }
+    }
+
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        match *payload {
+            [Self::JIGGLE, active] => Some(SyncMessage::Jiggle(active != 0)),
+            [Self::SENSOR_STATUS, status] => {
+                Some(SyncMessage::SensorStatus(SensorStatus::from_u8(status)))
+            }
+            [Self::SENSOR_DIAG, squal, raw_data_sum, max_raw, min_raw, shutter_lo, shutter_hi] => {
+                Some(SyncMessage::SensorDiag(SensorDiag {
+                    squal,
+                    raw_data_sum,
+                    max_raw,
+                    min_raw,
+                    shutter: u16::from_le_bytes([shutter_lo, shutter_hi]),
+                }))
+            }
+            [Self::DISPLAY_COMMAND, command] => {
+                DisplayCommand::from_u8(command).map(SyncMessage::DisplayCommand)
+            }
+            [Self::DISPLAY_SETTINGS, contrast, display_on] => {
+                Some(SyncMessage::DisplaySettings(DisplaySettings {
+                    contrast,
+                    display_on: display_on != 0,
+                }))
+            }
+            [Self::TYPING_SUMMARY, ref summary @ ..] if summary.len() == 16 => {
+                let u32_at = |i: usize| u32::from_le_bytes(summary[i..i + 4].try_into().unwrap());
+                Some(SyncMessage::TypingSummary(TypingSummary {
+                    total_presses: u32_at(0),
+                    session_presses: u32_at(4),
+                    session_typing_secs: u32_at(8),
+                    layer_percent: summary[12..16].try_into().unwrap(),
+                }))
+            }
+            [Self::MODIFIERS, bits] => Some(SyncMessage::Modifiers(Modifiers::from_bits(bits))),
+            [Self::NOTIFICATION, ttl_lo, ttl_hi, ref text @ ..] => Some(SyncMessage::Notification(
+                Notification::from_bytes(text, u16::from_le_bytes([ttl_lo, ttl_hi])),
+            )),
+            [Self::POINTING_STATE, cpi_lo, cpi_hi, mode] => {
+                Some(SyncMessage::PointingState(PointingState {
+                    cpi: u16::from_le_bytes([cpi_lo, cpi_hi]),
+                    mode: PointingMode::from_u8(mode),
+                }))
+            }
+            [Self::HEARTBEAT] => Some(SyncMessage::Heartbeat),
+            [Self::USB_STATE, state] => Some(SyncMessage::UsbState(UsbState::from_u8(state))),
+            [Self::ACTIVITY] => Some(SyncMessage::Activity),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::NOTIFICATION_TTL;
+
+    /// One message of each kind, in the order of their tags
+    fn messages() -> [SyncMessage; 12] {
+        [
+            SyncMessage::Jiggle(true),
+            SyncMessage::SensorStatus(SensorStatus::SromIdMismatch),
+            SyncMessage::SensorDiag(SensorDiag {
+                squal: 0x40,
+                raw_data_sum: 0x81,
+                max_raw: 0xf0,
+                min_raw: 0x02,
+                shutter: 0x1234,
+            }),
+            SyncMessage::DisplayCommand(DisplayCommand::ContrastDown),
+            SyncMessage::DisplaySettings(DisplaySettings {
+                contrast: 0x3f,
+                display_on: false,
+            }),
+            SyncMessage::TypingSummary(TypingSummary {
+                total_presses: 123_456_789,
+                session_presses: 4321,
+                session_typing_secs: 987,
+                layer_percent: [70, 20, 9, 1],
+            }),
+            SyncMessage::Modifiers(Modifiers {
+                ctrl: true,
+                shift: false,
+                alt: true,
+                gui: false,
+                tap_hold: true,
+            }),
+            SyncMessage::Notification(Notification::new("CPI 2400", NOTIFICATION_TTL)),
+            SyncMessage::PointingState(PointingState {
+                cpi: 1600,
+                mode: PointingMode::Sniping,
+            }),
+            SyncMessage::Heartbeat,
+            SyncMessage::UsbState(UsbState::Suspended),
+            SyncMessage::Activity,
+        ]
+    }
+
+    fn frame(message: SyncMessage) -> Vec<u8> {
+        let mut payload = [0; MAX_PAYLOAD];
+        let len = message.encode(&mut payload);
+        let mut frame = [0; MAX_FRAME];
+        let len = encode_frame(CHANNEL_SYNC, &payload[..len], &mut frame);
+        frame[..len].to_vec()
+    }
+
+    /// Channel and payload of the frames in `bytes` and the frame errors, in the order found
+    fn frames(bytes: &[u8]) -> Vec<Result<(u8, Vec<u8>), FrameError>> {
+        let mut parser = FrameParser::new();
+        let mut frames = Vec::new();
+        for &byte in bytes {
+            if let Some(frame) = parser.push(byte) {
+                frames.push(frame.map(|frame| (frame.channel, frame.payload.to_vec())));
+            }
+        }
+        frames
+    }
+
+    /// Messages decoded from `bytes` and the frame errors, in the order found
+    fn parse(bytes: &[u8]) -> Vec<Result<SyncMessage, FrameError>> {
+        let frames = frames(bytes).into_iter();
+        frames
+            .map(|frame| {
+                frame.map(|(channel, payload)| {
+                    assert_eq!(channel, CHANNEL_SYNC);
+                    SyncMessage::decode(&payload).unwrap()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tags_messages_in_order() {
+        for (tag, message) in messages().into_iter().enumerate() {
+            let mut payload = [0; MAX_PAYLOAD];
+            message.encode(&mut payload);
+            assert_eq!(payload[0] as usize, tag, "{message:?}");
+        }
+    }
+
+    #[test]
+    fn round_trips_every_message() {
+        for message in messages() {
+            assert_eq!(parse(&frame(message)), [Ok(message)]);
+        }
+    }
+
+    #[test]
+    fn round_trips_every_usb_state() {
+        for state in [
+            UsbState::Detached,
+            UsbState::Powered,
+            UsbState::Configured,
+            UsbState::Suspended,
+            UsbState::VialUnlocked,
+        ] {
+            let message = SyncMessage::UsbState(state);
+            assert_eq!(parse(&frame(message)), [Ok(message)]);
+        }
+    }
+
+    #[test]
+    fn parses_frames_back_to_back() {
+        let bytes: Vec<u8> = messages().into_iter().flat_map(frame).collect();
+        let expected: Vec<_> = messages().into_iter().map(Ok).collect();
+        assert_eq!(parse(&bytes), expected);
+    }
+
+    #[test]
+    fn passes_rmk_frames_through() {
+        let mut frame = [0; MAX_FRAME];
+        let len = encode_frame(CHANNEL_RMK, &[FRAME_SYNC, 0, 0xff], &mut frame);
+        assert_eq!(
+            frames(&frame[..len]),
+            [Ok((CHANNEL_RMK, vec![FRAME_SYNC, 0, 0xff]))]
+        );
+    }
+
+    #[test]
+    fn drops_a_frame_with_a_bad_crc() {
+        let mut bad = frame(SyncMessage::Jiggle(true));
+        *bad.last_mut().unwrap() ^= 0x01;
+        let bytes = [bad, frame(SyncMessage::Activity)].concat();
+        assert_eq!(
+            parse(&bytes),
+            [Err(FrameError::Crc), Ok(SyncMessage::Activity)]
+        );
+    }
+
+    #[test]
+    fn drops_a_frame_with_a_flipped_payload_bit() {
+        let mut bad = frame(SyncMessage::Modifiers(Modifiers::default()));
+        bad[4] ^= 0x04;
+        assert_eq!(parse(&bad), [Err(FrameError::Crc)]);
+    }
+
+    #[test]
+    fn waits_for_the_rest_of_a_frame() {
+        let frame = frame(SyncMessage::Notification(Notification::new(
+            "HELLO",
+            NOTIFICATION_TTL,
+        )));
+        let mut parser = FrameParser::new();
+        let (last, head) = frame.split_last().unwrap();
+        assert!(head.iter().all(|&byte| parser.push(byte).is_none()));
+        assert!(matches!(parser.push(*last), Some(Ok(_))));
+    }
+
+    #[test]
+    fn recovers_from_a_truncated_frame() {
+        let notification =
+            SyncMessage::Notification(Notification::new("A LONG MESSAGE", NOTIFICATION_TTL));
+        let truncated = &frame(notification)[..6];
+        let mut bytes = truncated.to_vec();
+        // The cut off frame swallows what follows until its length is reached
+        for _ in 0..6 {
+            bytes.extend(frame(SyncMessage::Heartbeat));
+        }
+        bytes.extend(frame(SyncMessage::Activity));
+
+        let parsed = parse(&bytes);
+        assert_eq!(parsed[0], Err(FrameError::Crc));
+        assert_eq!(parsed.last(), Some(&Ok(SyncMessage::Activity)));
+        assert!(parsed[1..parsed.len() - 1]
+            .iter()
+            .all(|message| *message == Ok(SyncMessage::Heartbeat)));
+    }
+
+    #[test]
+    fn rejects_truncated_payloads() {
+        for message in messages() {
+            let mut payload = [0; MAX_PAYLOAD];
+            let len = message.encode(&mut payload);
+            // A notification with a shorter text is still a notification
+            if matches!(message, SyncMessage::Notification(_)) {
+                assert_eq!(SyncMessage::decode(&payload[..2]), None);
+                continue;
+            }
+            for len in 0..len {
+                assert_eq!(SyncMessage::decode(&payload[..len]), None, "{message:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn resyncs_after_junk() {
+        let junk = [0x00, 0xff, 0x12, 0x5a, 0x01];
+        let bytes = [&junk[..], &frame(SyncMessage::Jiggle(false))].concat();
+        assert_eq!(parse(&bytes), [Ok(SyncMessage::Jiggle(false))]);
+    }
+
+    #[test]
+    fn resyncs_after_a_sync_byte_with_a_bad_length() {
+        let bytes = [
+            &[FRAME_SYNC, CHANNEL_SYNC, MAX_PAYLOAD as u8 + 1][..],
+            &frame(SyncMessage::Heartbeat),
+        ]
+        .concat();
+        assert_eq!(
+            parse(&bytes),
+            [Err(FrameError::Length), Ok(SyncMessage::Heartbeat)]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_tags_and_values() {
+        assert_eq!(SyncMessage::decode(&[12]), None);
+        assert_eq!(SyncMessage::decode(&[]), None);
+        // No display command 7
+        assert_eq!(SyncMessage::decode(&[3, 7]), None);
+    }
+}
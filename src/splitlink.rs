@@ -0,0 +1,385 @@
+//! Carries our own messages from the central to the peripheral over the serial split link.
+//!
+//! rmk only syncs its own state (layer, LEDs, WPM, ...) to the peripheral. [`SplitLink`] wraps
+//! the UART of both halves and frames everything sent over it, so our messages can travel
+//! between rmk's frames:
+//!
+//! `SYNC | channel | length | payload | CRC-8`
+//!
+//! The framing and the messages are in [`crate::splitframe`]. Frames of the rmk channel are handed to rmk as a plain byte stream, frames of the sync
+//! channel are decoded into [`SyncMessage`]s and published as events on the receiving half.
+//!
+//! The UART is split: rmk reads through [`SplitLink`], while its writes and ours share the
+//! [`SplitTx`]. Our messages are sent by the [`SplitLinkWriter`] task, never from inside a read
+//! that rmk may drop halfway.
+//!
+//! Both halves send a heartbeat when they had nothing to send for a second, so the receiving
+//! half notices a dropped link. It publishes its [`LinkStatus`] when the connection changes and
+//! at most every second while the error counters change.
+use crate::activitystate::{ActivityEvent, ActivityThrottle};
+use crate::displaycommands::DisplayCommandEvent;
+use crate::displaysettings::DisplaySettingsEvent;
+use crate::jigglemode::JiggleEvent;
+use crate::linkstate::{LinkStatus, LinkStatusEvent};
+use crate::modifierstate::ModifiersEvent;
+use crate::notification::NotificationEvent;
+use crate::pointingstate::PointingStateEvent;
+use crate::sensorstate::{SensorDiagEvent, SensorStatusEvent};
+use crate::splitframe::{
+    encode_frame, FrameError, FrameParser, SyncMessage, CHANNEL_RMK, CHANNEL_SYNC, MAX_FRAME,
+    MAX_PAYLOAD,
+};
+use crate::typingstate::TypingSummaryEvent;
+use crate::usbstate::UsbStateEvent;
+use defmt::{debug, info, warn};
+use embassy_futures::select::{select, Either};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_sync::mutex::Mutex;
+use embassy_time::{Duration, Instant, Timer};
+use embedded_io_async::{ErrorType, Read, Write};
+use rmk::event::{publish_event, KeyboardEvent};
+use rmk_macro::processor;
+
+/// Longest time without sending anything before a heartbeat is sent
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// Time without a valid frame until the link counts as dropped, a few missed heartbeats
+const LINK_TIMEOUT: Duration = Duration::from_secs(3);
+/// Interval of checking for a dropped link while nothing arrives
+const LINK_TICK: Duration = Duration::from_millis(250);
+/// Shortest time between two `LinkStatusEvent`s for changed counters
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Messages waiting to be sent to the other half
+pub static SYNC_TX: Channel<CriticalSectionRawMutex, SyncMessage, 8> = Channel::new();
+
+/// Publishes a received message as event on the receiving half
+fn publish(message: SyncMessage) {
+    match message {
+        SyncMessage::Jiggle(active) => publish_event(JiggleEvent(active)),
+        SyncMessage::SensorStatus(status) => publish_event(SensorStatusEvent(status)),
+        SyncMessage::SensorDiag(diag) => publish_event(SensorDiagEvent(diag)),
+        SyncMessage::DisplayCommand(command) => publish_event(DisplayCommandEvent(command)),
+        SyncMessage::DisplaySettings(settings) => publish_event(DisplaySettingsEvent(settings)),
+        SyncMessage::TypingSummary(summary) => publish_event(TypingSummaryEvent(summary)),
+        SyncMessage::Modifiers(modifiers) => publish_event(ModifiersEvent(modifiers)),
+        SyncMessage::Notification(notification) => publish_event(NotificationEvent(notification)),
+        SyncMessage::PointingState(state) => publish_event(PointingStateEvent(state)),
+        SyncMessage::UsbState(state) => publish_event(UsbStateEvent(state)),
+        SyncMessage::Activity => publish_event(ActivityEvent),
+        SyncMessage::Heartbeat => {}
+    }
+}
+
+/// Sending half of the split UART, shared by rmk's frames and the [`SplitLinkWriter`]
+pub struct SplitTx<W> {
+    inner: Mutex<CriticalSectionRawMutex, TxState<W>>,
+}
+
+struct TxState<W> {
+    uart: W,
+    /// Last frame sent, for the heartbeat
+    last_tx: Instant,
+}
+
+impl<W: Write> SplitTx<W> {
+    pub fn new(uart: W) -> Self {
+        Self {
+            inner: Mutex::new(TxState {
+                uart,
+                last_tx: Instant::now(),
+            }),
+        }
+    }
+
+    /// Sends a whole frame, frames of both writers don't interleave
+    async fn write_frame(&self, channel: u8, payload: &[u8]) -> Result<(), W::Error> {
+        let mut frame = [0u8; MAX_FRAME];
+        let len = encode_frame(channel, payload, &mut frame);
+        let mut tx = self.inner.lock().await;
+        tx.last_tx = Instant::now();
+        tx.uart.write_all(&frame[..len]).await
+    }
+
+    async fn send(&self, message: SyncMessage) -> Result<(), W::Error> {
+        let mut payload = [0u8; MAX_PAYLOAD];
+        let len = message.encode(&mut payload);
+        self.write_frame(CHANNEL_SYNC, &payload[..len]).await
+    }
+
+    async fn flush(&self) -> Result<(), W::Error> {
+        self.inner.lock().await.uart.flush().await
+    }
+
+    async fn last_tx(&self) -> Instant {
+        self.inner.lock().await.last_tx
+    }
+}
+
+/// Sends the queued [`SyncMessage`]s and the heartbeat, run it on both halves next to rmk
+pub struct SplitLinkWriter<'a, W> {
+    tx: &'a SplitTx<W>,
+}
+
+impl<'a, W: Write> SplitLinkWriter<'a, W> {
+    pub fn new(tx: &'a SplitTx<W>) -> Self {
+        Self { tx }
+    }
+
+    pub async fn run(&mut self) {
+        loop {
+            let heartbeat_at = self.tx.last_tx().await + HEARTBEAT_INTERVAL;
+            let message = match select(SYNC_TX.receive(), Timer::at(heartbeat_at)).await {
+                Either::First(message) => message,
+                // rmk may have sent meanwhile
+                Either::Second(()) if self.tx.last_tx().await.elapsed() < HEARTBEAT_INTERVAL => {
+                    continue
+                }
+                Either::Second(()) => SyncMessage::Heartbeat,
+            };
+            if self.tx.send(message).await.is_err() {
+                warn!("Split link failed to send {}", message);
+            }
+        }
+    }
+}
+
+/// Receiving half of the split UART with the shared [`SplitTx`], use it on both halves in place
+/// of the UART itself
+pub struct SplitLink<'a, R, W> {
+    uart: R,
+    tx: &'a SplitTx<W>,
+    /// Raw bytes read from the UART and not parsed yet
+    raw: [u8; 32],
+    raw_pos: usize,
+    raw_len: usize,
+    /// Frame being received
+    parser: FrameParser,
+    /// Payload of the last rmk frame, not yet read by rmk
+    rmk_rx: [u8; MAX_PAYLOAD],
+    rmk_rx_pos: usize,
+    rmk_rx_len: usize,
+    status: LinkStatus,
+    /// Last valid frame received
+    last_rx: Instant,
+    /// Status of the last `LinkStatusEvent`, `None` before the first one
+    published: Option<LinkStatus>,
+    published_at: Instant,
+}
+
+impl<'a, R: Read, W: Write> SplitLink<'a, R, W> {
+    pub fn new(uart: R, tx: &'a SplitTx<W>) -> Self {
+        Self {
+            uart,
+            tx,
+            raw: [0; 32],
+            raw_pos: 0,
+            raw_len: 0,
+            parser: FrameParser::new(),
+            rmk_rx: [0; MAX_PAYLOAD],
+            rmk_rx_pos: 0,
+            rmk_rx_len: 0,
+            status: LinkStatus::default(),
+            last_rx: Instant::now(),
+            published: None,
+            published_at: Instant::now(),
+        }
+    }
+
+    /// Publishes the link status if the connection changed, or the counters did a while after
+    /// the last time
+    fn update_status(&mut self) {
+        // Nothing published before the first frame or timeout, so a booting half doesn't warn
+        if self.status.frames == 0 && self.last_rx.elapsed() < LINK_TIMEOUT {
+            return;
+        }
+        self.status.connected = self.status.frames > 0 && self.last_rx.elapsed() < LINK_TIMEOUT;
+        let connection_changed = self
+            .published
+            .is_none_or(|published| published.connected != self.status.connected);
+        if connection_changed {
+            if self.status.connected {
+                info!("Split link connected");
+            } else {
+                warn!("Split link dropped");
+            }
+        } else if self.published == Some(self.status)
+            || self.published_at.elapsed() < STATUS_INTERVAL
+        {
+            return;
+        }
+        self.published = Some(self.status);
+        self.published_at = Instant::now();
+        publish_event(LinkStatusEvent(self.status));
+    }
+
+    /// Parses the buffered raw bytes until an rmk frame is complete
+    fn parse(&mut self) {
+        while self.raw_pos < self.raw_len && self.rmk_rx_len == 0 {
+            let byte = self.raw[self.raw_pos];
+            self.raw_pos += 1;
+
+            let frame = match self.parser.push(byte) {
+                None => continue,
+                Some(Ok(frame)) => frame,
+                // Not a frame, resync
+                Some(Err(FrameError::Length)) => {
+                    self.status.framing_errors += 1;
+                    continue;
+                }
+                Some(Err(FrameError::Crc)) => {
+                    warn!("Split link frame CRC mismatch");
+                    self.status.crc_errors += 1;
+                    continue;
+                }
+            };
+            self.status.frames += 1;
+            self.last_rx = Instant::now();
+            match frame.channel {
+                CHANNEL_RMK => {
+                    self.rmk_rx[..frame.payload.len()].copy_from_slice(frame.payload);
+                    self.rmk_rx_pos = 0;
+                    self.rmk_rx_len = frame.payload.len();
+                }
+                CHANNEL_SYNC => match SyncMessage::decode(frame.payload) {
+                    Some(message) => {
+                        debug!("Split link received {}", message);
+                        publish(message);
+                    }
+                    None => {
+                        warn!("Split link received unknown message");
+                        self.status.framing_errors += 1;
+                    }
+                },
+                _ => {
+                    warn!("Split link received unknown channel");
+                    self.status.framing_errors += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<R: ErrorType, W> ErrorType for SplitLink<'_, R, W> {
+    type Error = R::Error;
+}
+
+impl<R: Read, W: Write<Error = R::Error>> Read for SplitLink<'_, R, W> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            self.parse();
+            self.update_status();
+            if self.rmk_rx_pos < self.rmk_rx_len {
+                let n = buf.len().min(self.rmk_rx_len - self.rmk_rx_pos);
+                buf[..n].copy_from_slice(&self.rmk_rx[self.rmk_rx_pos..self.rmk_rx_pos + n]);
+                self.rmk_rx_pos += n;
+                if self.rmk_rx_pos == self.rmk_rx_len {
+                    self.rmk_rx_len = 0;
+                }
+                return Ok(n);
+            }
+
+            // The tick only wakes the status update, dropping the read loses nothing
+            if let Either::First(n) =
+                select(self.uart.read(&mut self.raw), Timer::after(LINK_TICK)).await
+            {
+                self.raw_pos = 0;
+                self.raw_len = n?;
+            }
+        }
+    }
+}
+
+impl<R: Read, W: Write<Error = R::Error>> Write for SplitLink<'_, R, W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let n = buf.len().min(MAX_PAYLOAD);
+        self.tx.write_frame(CHANNEL_RMK, &buf[..n]).await?;
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.tx.flush().await
+    }
+}
+
+/// Queues the events shown on the peripheral for sending over the split link
+#[processor(subscribe = [JiggleEvent, SensorStatusEvent, SensorDiagEvent, DisplayCommandEvent, DisplaySettingsEvent, TypingSummaryEvent, ModifiersEvent, NotificationEvent, PointingStateEvent, UsbStateEvent, KeyboardEvent, ActivityEvent])]
+pub struct SplitForwarder {
+    dropped: u32,
+    /// Keys and motion of the central, passed on to keep the display awake
+    activity: ActivityThrottle,
+}
+
+impl Default for SplitForwarder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SplitForwarder {
+    pub fn new() -> Self {
+        Self {
+            dropped: 0,
+            activity: ActivityThrottle::new(),
+        }
+    }
+
+    fn forward(&mut self, message: SyncMessage) {
+        if SYNC_TX.try_send(message).is_err() {
+            self.dropped += 1;
+            warn!("Split link queue full, dropped {} messages", self.dropped);
+        }
+    }
+
+    async fn on_jiggle_event(&mut self, event: JiggleEvent) {
+        self.forward(SyncMessage::Jiggle(event.0));
+    }
+
+    async fn on_sensor_status_event(&mut self, event: SensorStatusEvent) {
+        self.forward(SyncMessage::SensorStatus(event.0));
+    }
+
+    async fn on_sensor_diag_event(&mut self, event: SensorDiagEvent) {
+        self.forward(SyncMessage::SensorDiag(event.0));
+    }
+
+    async fn on_display_command_event(&mut self, event: DisplayCommandEvent) {
+        self.forward(SyncMessage::DisplayCommand(event.0));
+    }
+
+    async fn on_display_settings_event(&mut self, event: DisplaySettingsEvent) {
+        self.forward(SyncMessage::DisplaySettings(event.0));
+    }
+
+    async fn on_typing_summary_event(&mut self, event: TypingSummaryEvent) {
+        self.forward(SyncMessage::TypingSummary(event.0));
+    }
+
+    async fn on_modifiers_event(&mut self, event: ModifiersEvent) {
+        self.forward(SyncMessage::Modifiers(event.0));
+    }
+
+    async fn on_notification_event(&mut self, event: NotificationEvent) {
+        self.forward(SyncMessage::Notification(event.0));
+    }
+
+    async fn on_pointing_state_event(&mut self, event: PointingStateEvent) {
+        self.forward(SyncMessage::PointingState(event.0));
+    }
+
+    async fn on_usb_state_event(&mut self, event: UsbStateEvent) {
+        self.forward(SyncMessage::UsbState(event.0));
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        if event.pressed && self.activity.pass() {
+            self.forward(SyncMessage::Activity);
+        }
+    }
+
+    async fn on_activity_event(&mut self, _event: ActivityEvent) {
+        if self.activity.pass() {
+            self.forward(SyncMessage::Activity);
+        }
+    }
+}
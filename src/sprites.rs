@@ -0,0 +1,6 @@
+//! Animations converted from the images in `assets/sprites` by build.rs, for use in the rules of
+//! a [`crate::animation::Pet`]
+#[allow(unused_imports)]
+use crate::animation::{Animation, Frame, Tempo};
+
+include!(concat!(env!("OUT_DIR"), "/sprites_generated.rs"));
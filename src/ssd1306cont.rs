@@ -1,542 +1,368 @@
+use crate::activitystate::ActivityEvent;
+use crate::displaycommands::{DisplayCommand, DisplayCommandEvent};
+use crate::displayconfig::{DIM_TIMEOUT, OFF_TIMEOUT, SHIFT_INTERVAL};
+use crate::displaypages::{DisplayState, PageId, Pages, SettingsPage};
+use crate::displaysettings::{DisplaySettings, DisplaySettingsEvent};
 use crate::jigglemode::JiggleEvent;
-use core::fmt::Write;
-use defmt::debug;
-use display_interface::AsyncWriteOnlyDataCommand;
-use embedded_graphics::image::{Image, ImageRaw};
-use embedded_graphics::mono_font::MonoFont;
-use embedded_graphics::mono_font::MonoTextStyle;
-use embedded_graphics::{
-    mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
-    pixelcolor::BinaryColor,
-    prelude::*,
-    text::{Baseline, Text},
-};
+use crate::linkstate::LinkStatusEvent;
+use crate::modifierstate::ModifiersEvent;
+use crate::notification::{Notification, NotificationEvent, Notifications, NOTIFICATION_TTL};
+use crate::oled::Oled;
+use crate::pointingstate::PointingStateEvent;
+use crate::sensorstate::{SensorDiagEvent, SensorStatusEvent};
+use crate::typingstate::TypingSummaryEvent;
+use crate::usbstate::UsbStateEvent;
+use defmt::{debug, info, warn, Debug2Format};
+use display_interface::{AsyncWriteOnlyDataCommand, DisplayError};
+use embassy_time::{Duration, Instant};
+use embedded_graphics::prelude::*;
+use rmk::event::KeyboardEvent;
+use rmk::event::LayerChangeEvent;
 use rmk::event::LedIndicatorEvent;
 use rmk::event::WpmUpdateEvent;
-use rmk::heapless::String;
-use rmk::{event::LayerChangeEvent, types::led_indicator::LedIndicator};
 use rmk_macro::processor;
-use ssd1306::mode::BufferedGraphicsModeAsync;
-use ssd1306::size::DisplaySizeAsync;
-use ssd1306::Ssd1306Async;
-
-const FONT: MonoFont<'_> = FONT_6X10;
-
-#[processor(subscribe = [LayerChangeEvent, LedIndicatorEvent, JiggleEvent, WpmUpdateEvent], poll_interval = 50)]
-pub struct Ssd1306Controller<'a, DI, SIZE>
-where
-    SIZE: DisplaySizeAsync,
-    DI: AsyncWriteOnlyDataCommand,
-{
-    current_indicators: LedIndicator,
-    current_layer: u8,
-    jiggle_active: bool,
-    current_wpm: u16,
-    text_style_norm: MonoTextStyle<'a, BinaryColor>,
-    text_style_inv: MonoTextStyle<'a, BinaryColor>,
-    char_width: u32,
-    char_height: u32,
-    display: Ssd1306Async<DI, SIZE, BufferedGraphicsModeAsync<SIZE>>,
-    anim_frame_dur: u16,
-    anim_frame_old_loc: u16,
-    anim_frame_old: u16,
-    anim_timer: u16,
-    current_frame: usize,
+
+/// Offsets the content is moved through against burn-in. A pixel at most, so only the edge of
+/// the cat is cut off.
+const PIXEL_SHIFTS: [Point; 4] = [
+    Point::new(0, 0),
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+];
+
+/// Precharge of the display while on, as the ssd1306 crate's brightness levels
+const PRECHARGE: u8 = 0x2;
+/// Precharge and contrast of the dimmed display, the ssd1306 crate's dimmest brightness
+const DIMMED_PRECHARGE: u8 = 0x1;
+const DIMMED_CONTRAST: u8 = 0x00;
+
+/// Wait before the first retry after a failed transfer, doubled with every further failure up
+/// to `MAX_RETRY_BACKOFF`
+const MIN_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+enum Power {
+    On,
+    Dimmed,
+    Off,
 }
 
-impl<'a, DI, SIZE> Ssd1306Controller<'a, DI, SIZE>
-where
-    SIZE: DisplaySizeAsync,
-    DI: AsyncWriteOnlyDataCommand,
-{
-    pub fn new(display: Ssd1306Async<DI, SIZE, BufferedGraphicsModeAsync<SIZE>>) -> Self {
-        let text_style_norm = MonoTextStyleBuilder::new()
-            .font(&FONT)
-            .text_color(BinaryColor::On)
-            .build();
-
-        let text_style_inv = MonoTextStyleBuilder::new()
-            .font(&FONT)
-            .text_color(BinaryColor::Off)
-            .background_color(BinaryColor::On)
-            .build();
-        let char_width = FONT.character_size.width;
-        let char_height = FONT.character_size.height;
+impl Power {
+    /// Power of the display after `idle` without activity
+    fn after(idle: Duration) -> Self {
+        if OFF_TIMEOUT.is_some_and(|timeout| idle >= timeout) {
+            Power::Off
+        } else if DIM_TIMEOUT.is_some_and(|timeout| idle >= timeout) {
+            Power::Dimmed
+        } else {
+            Power::On
+        }
+    }
+}
+
+/// Shows the display pages on the OLED, whichever controller drives it
+#[processor(subscribe = [LayerChangeEvent, LedIndicatorEvent, JiggleEvent, WpmUpdateEvent, SensorStatusEvent, SensorDiagEvent, DisplayCommandEvent, DisplaySettingsEvent, TypingSummaryEvent, ModifiersEvent, NotificationEvent, PointingStateEvent, LinkStatusEvent, UsbStateEvent, KeyboardEvent, ActivityEvent], poll_interval = 50)]
+pub struct Ssd1306Controller<DI: AsyncWriteOnlyDataCommand> {
+    state: DisplayState,
+    pages: Pages,
+    page: PageId,
+    /// When the current page was switched to, for the carousel
+    page_since: Instant,
+    /// Last key, ball, layer or WPM event, the display is dimmed and switched off after inactivity
+    last_activity: Instant,
+    power: Power,
+    /// Contrast and on/off chosen on the central
+    settings: DisplaySettings,
+    /// Settings changed since they were applied to the display
+    settings_changed: bool,
+    /// Index into `PIXEL_SHIFTS`
+    shift: usize,
+    shift_since: Instant,
+    notifications: Notifications,
+    /// The state changed since the page was last drawn
+    redraw: bool,
+    display: Oled<DI>,
+    /// The display is initialized and the last transfer succeeded
+    ready: bool,
+    /// When the display is initialized again after a failure
+    retry_at: Instant,
+    backoff: Duration,
+}
 
+impl<DI: AsyncWriteOnlyDataCommand> Ssd1306Controller<DI> {
+    /// The display is initialized on the first poll, and again after transfers failed
+    pub fn new(display: Oled<DI>) -> Self {
         Self {
-            current_indicators: 0.into(),
-            current_layer: 0,
-            current_wpm: 0,
-            jiggle_active: false,
-            text_style_norm,
-            text_style_inv,
-            char_width,
-            char_height,
+            state: DisplayState::new(),
+            pages: Pages::new(),
+            page: PageId::Status,
+            page_since: Instant::now(),
+            last_activity: Instant::now(),
+            power: Power::On,
+            settings: DisplaySettings::default(),
+            settings_changed: false,
+            shift: 0,
+            shift_since: Instant::now(),
+            notifications: Notifications::new(),
+            redraw: true,
             display,
-            anim_frame_dur: 66,
-            anim_frame_old: 66,
-            anim_frame_old_loc: 1,
-            anim_timer: 0,
-            current_frame: 0,
+            ready: false,
+            retry_at: Instant::now(),
+            backoff: MIN_RETRY_BACKOFF,
         }
     }
 
     async fn on_wpm_update_event(&mut self, event: WpmUpdateEvent) {
-        self.current_wpm = event.wpm;
+        self.redraw |= event.wpm != self.state.wpm;
+        self.state.wpm = event.wpm;
+        self.state.wpm_peak = self.state.wpm_peak.max(event.wpm);
+        // The WPM decays to 0 after typing, only count actual typing
+        if event.wpm > 0 {
+            self.wake();
+        }
     }
 
-    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
-        if event.layer != self.current_layer {
-            self.current_layer = event.layer;
+    /// Keys of this half, the central passes on its keys and the ball as `ActivityEvent`
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        if event.pressed {
+            self.wake();
         }
     }
 
+    async fn on_activity_event(&mut self, _event: ActivityEvent) {
+        self.wake();
+    }
+
+    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
+        self.state.layer = event.layer;
+        self.redraw = true;
+        self.wake();
+    }
+
     async fn on_led_indicator_event(&mut self, event: LedIndicatorEvent) {
         debug!("got led ind event");
-        self.current_indicators = event.indicator;
+        self.state.indicators = event.indicator;
+        self.redraw = true;
+        self.wake();
     }
 
     async fn on_jiggle_event(&mut self, event: JiggleEvent) {
         debug!("got jiggle event: {}", event.0);
-        self.jiggle_active = event.0;
+        self.state.jiggle_active = event.0;
+        self.redraw = true;
+        self.wake();
     }
 
-    fn draw_indicators(&mut self, y: i32) {
-        let indicators = [
-            (
-                "N",
-                if self.current_indicators.num_lock() {
-                    self.text_style_inv
-                } else {
-                    self.text_style_norm
-                },
-            ),
-            (
-                "C",
-                if self.current_indicators.caps_lock() {
-                    self.text_style_inv
-                } else {
-                    self.text_style_norm
-                },
-            ),
-            (
-                "S",
-                if self.current_indicators.scroll_lock() {
-                    self.text_style_inv
-                } else {
-                    self.text_style_norm
-                },
-            ),
-        ];
-
-        let mut cursor_x = 0;
-
-        for (i, (ch, style)) in indicators.iter().enumerate() {
-            Text::with_baseline(ch, Point::new(cursor_x, y), *style, Baseline::Top)
-                .draw(&mut self.display)
-                .unwrap();
-
-            cursor_x += self.char_width as i32;
-
-            if i < indicators.len() - 1 {
-                Text::with_baseline(
-                    " ",
-                    Point::new(cursor_x, y),
-                    self.text_style_norm,
-                    Baseline::Top,
-                )
-                .draw(&mut self.display)
-                .unwrap();
-
-                cursor_x += self.char_width as i32;
-            }
-        }
+    async fn on_modifiers_event(&mut self, event: ModifiersEvent) {
+        self.state.modifiers = event.0;
+        self.redraw = true;
+        self.wake();
+    }
+
+    async fn on_notification_event(&mut self, event: NotificationEvent) {
+        debug!("got notification: {=str}", event.0.text());
+        self.notifications.push(event.0);
+        self.wake();
     }
-    fn draw_layer(&mut self, y: i32) {
-        let layer_name = match self.current_layer {
-            0 => "DVORA",
-            1 => "LOWER",
-            2 => "RAISE",
-            _ => "UNKNO",
-        };
 
-        Text::with_baseline(
-            layer_name,
-            Point::new(0, y),
-            self.text_style_norm,
-            Baseline::Top,
-        )
-        .draw(&mut self.display)
-        .unwrap();
+    async fn on_pointing_state_event(&mut self, event: PointingStateEvent) {
+        self.state.pointing = event.0;
+        self.redraw = true;
     }
-    fn draw_compose_jiggle(&mut self, y: i32) {
-        let compose_parts = [
-            ("CO", self.current_indicators.compose()),
-            ("JI", self.jiggle_active),
-        ];
-
-        let mut cursor_x = 0;
-
-        for (i, (part, invert)) in compose_parts.iter().enumerate() {
-            let style = if *invert {
-                self.text_style_inv
-            } else {
-                self.text_style_norm
-            };
-
-            Text::with_baseline(part, Point::new(cursor_x, y), style, Baseline::Top)
-                .draw(&mut self.display)
-                .unwrap();
-
-            cursor_x += self.char_width as i32 * part.len() as i32;
-
-            if i < compose_parts.len() - 1 {
-                Text::with_baseline(
-                    " ",
-                    Point::new(cursor_x, y),
-                    self.text_style_norm,
-                    Baseline::Top,
-                )
-                .draw(&mut self.display)
-                .unwrap();
-
-                cursor_x += self.char_width as i32;
+
+    async fn on_link_status_event(&mut self, event: LinkStatusEvent) {
+        let was_connected = self.state.link.map(|link| link.connected);
+        if was_connected != Some(event.0.connected) {
+            // Nothing to report for the first connection after boot
+            if was_connected.is_some() || !event.0.connected {
+                let text = if event.0.connected {
+                    "LINK OK"
+                } else {
+                    "LINK LOST"
+                };
+                self.notifications
+                    .push(Notification::new(text, NOTIFICATION_TTL));
             }
+            self.wake();
         }
-    }
-    fn draw_wpm(&mut self, y: i32) {
-        let mut wpm_text: String<16> = String::new();
-        write!(wpm_text, "W:{:>3}", self.current_wpm).unwrap();
-
-        Text::with_baseline(
-            &wpm_text,
-            Point::new(0, y),
-            self.text_style_norm,
-            Baseline::Top,
-        )
-        .draw(&mut self.display)
-        .unwrap();
+        self.state.link = Some(event.0);
+        self.redraw = true;
     }
 
-    fn draw_cat(&mut self, position: Point) {
-        let mut image = ImageRaw::<BinaryColor>::new(&CAT_SHOUT_EG[self.current_frame], 32);
-        if self.current_indicators.caps_lock() {
-            image = ImageRaw::<BinaryColor>::new(&CAT_SHOUT_EG[self.current_frame], 32);
-        } else if self.current_layer == 1 {
-            image = ImageRaw::<BinaryColor>::new(&CAT_WHISP_EG[self.current_frame], 32);
-        } else if self.current_layer == 2 {
-            image = ImageRaw::<BinaryColor>::new(&CAT_WAIT_EG[self.current_frame], 32);
-        } else if self.current_wpm <= Self::MIN_WALK_SPEED {
-            image = ImageRaw::<BinaryColor>::new(&CAT_IDLE_EG[self.current_frame], 32);
-        } else if self.current_wpm <= Self::MIN_RUN_SPEED {
-            image = ImageRaw::<BinaryColor>::new(&CAT_TAP_EG[self.current_frame], 32);
+    async fn on_usb_state_event(&mut self, event: UsbStateEvent) {
+        if self.state.usb != Some(event.0) {
+            debug!("got usb state event: {}", event.0);
+            self.state.usb = Some(event.0);
+            self.redraw = true;
+            self.wake();
         }
+    }
 
-        Image::new(&image, position)
-            .draw(&mut self.display)
-            .unwrap();
+    async fn on_sensor_status_event(&mut self, event: SensorStatusEvent) {
+        debug!("got sensor status event: {}", event.0);
+        self.state.sensor_status = Some(event.0);
+        self.redraw = true;
     }
 
-    const MIN_WALK_SPEED: u16 = 5;
-    const MIN_RUN_SPEED: u16 = 50;
-    const ANIMATION_SPEED: u16 = 200;
+    async fn on_sensor_diag_event(&mut self, event: SensorDiagEvent) {
+        self.state.sensor_diag = event.0;
+        self.redraw = true;
+    }
+
+    async fn on_typing_summary_event(&mut self, event: TypingSummaryEvent) {
+        self.state.typing = event.0;
+        self.redraw = true;
+    }
 
-    fn recalc_anim_frame_dur(&mut self) -> () {
-        if self.current_wpm <= Self::MIN_WALK_SPEED {
-            if self.current_frame == 0 {
-                self.anim_frame_dur = self.anim_frame_old_loc * 500;
-                if self.anim_frame_dur < 500 {
-                    self.anim_frame_dur = 500;
+    async fn on_display_command_event(&mut self, event: DisplayCommandEvent) {
+        self.wake();
+        self.redraw = true;
+        match event.0 {
+            DisplayCommand::ToggleDiagnostics => {
+                if self.page == PageId::Pointing {
+                    self.show_page(PageId::Status)
+                } else {
+                    self.show_page(PageId::Pointing)
                 }
-            } else {
-                self.anim_frame_dur = 100;
             }
-        } else if self.current_wpm <= Self::MIN_RUN_SPEED {
-            self.anim_frame_dur = 1400 - self.current_wpm * 1400 / Self::MIN_RUN_SPEED + 66;
-        } else {
-            self.anim_frame_dur = 66;
+            DisplayCommand::NextPage => self.show_page(self.page.next()),
+            DisplayCommand::PrevPage => self.show_page(self.page.prev()),
+            DisplayCommand::ToggleCarousel => {
+                self.state.carousel = !self.state.carousel;
+                self.page_since = Instant::now();
+            }
+            // Applied by the central, which sends the new `DisplaySettingsEvent`
+            DisplayCommand::ContrastUp
+            | DisplayCommand::ContrastDown
+            | DisplayCommand::ToggleDisplay => {}
         }
     }
 
-    fn animation_timer(&mut self) -> () {
-        if self.anim_timer > self.anim_frame_dur {
-            self.anim_timer = 0;
-            self.anim_frame_old_loc = self.anim_frame_old;
-            self.current_frame = if self.current_frame == 0 { 1 } else { 0 }; // currently there are only two frames per animation
-            debug!("new anim_timer: {}", self.anim_timer);
-            debug!("frame: {}", self.current_frame);
-        } else {
-            self.anim_timer += Self::ANIMATION_SPEED;
-            debug!("anim_timer: {}", self.anim_timer);
-            debug!("anim_frame_dur {}", self.anim_frame_dur);
+    async fn on_display_settings_event(&mut self, event: DisplaySettingsEvent) {
+        if event.0 != self.settings {
+            debug!("got display settings event: {}", event.0);
+            self.settings = event.0;
+            self.state.contrast = event.0.contrast;
+            self.settings_changed = true;
+            self.redraw = true;
         }
     }
 
-    pub async fn poll(&mut self) {
-        self.display.clear_buffer();
-
-        let line_height = self.char_height as i32 + 2;
-        let mut y = 0;
-
-        self.draw_indicators(y);
-        y += line_height;
-
-        self.draw_layer(y);
-        y += line_height;
-
-        self.draw_compose_jiggle(y);
-        y += line_height;
-
-        self.draw_wpm(y);
-
-        self.draw_cat(Point::new(0, 90));
-
-        self.display.flush().await.unwrap();
-
-        self.recalc_anim_frame_dur();
-        self.animation_timer();
+    fn show_page(&mut self, page: PageId) {
+        info!("Display page {}", page);
+        self.page = page;
+        self.page_since = Instant::now();
+        self.redraw = true;
     }
-}
 
-const CAT_IDLE_1: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x60, 0x60, 0x00, 0x80, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x80, 0x00, 0x60, 0x60, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x71, 0x12, 0x12, 0x11,
-    0x12, 0x72, 0xc1, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+    fn wake(&mut self) {
+        self.last_activity = Instant::now();
+    }
 
-const CAT_IDLE_2: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0x20, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x71, 0x12, 0x12, 0x11,
-    0x12, 0x72, 0xc1, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+    async fn set_power(&mut self, power: Power) -> Result<(), DisplayError> {
+        debug!("Display power {}", power);
+        match power {
+            Power::On => {
+                self.display
+                    .set_brightness(PRECHARGE, self.settings.contrast)
+                    .await?;
+                self.display.set_display_on(true).await?;
+            }
+            Power::Dimmed => {
+                self.display
+                    .set_brightness(DIMMED_PRECHARGE, DIMMED_CONTRAST)
+                    .await?;
+                self.display.set_display_on(true).await?;
+            }
+            Power::Off => self.display.set_display_on(false).await?,
+        }
+        self.power = power;
+        Ok(())
+    }
 
-const CAT_IDLE_EG: [[u8; 160]; 2] = {
-    // Erstes Bild konvertieren
-    const IMG0: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_IDLE_1, &mut out);
-        out
-    };
-    // Zweites Bild konvertieren (ersetze CAT_IDLE_2 durch dein zweites QMK‑Array)
-    const IMG1: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_IDLE_2, &mut out);
-        out
-    };
-    [IMG0, IMG1]
-};
-
-const CAT_TAP_1: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xa0, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x80, 0x00, 0x60, 0x60, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x90, 0x92, 0x91,
-    0x92, 0x12, 0x71, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x90, 0x90, 0x90, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x03, 0x03,
-    0x23, 0x33, 0x38, 0x39, 0x01, 0x02, 0x02, 0x39, 0x31, 0x20, 0x03, 0x03, 0x03, 0x03, 0x00, 0x00,
-];
+    /// Counts a failed transfer, the display is initialized again after the backoff
+    fn failed(&mut self, error: DisplayError) {
+        self.state.display_errors += 1;
+        warn!(
+            "Display error {}, retrying in {} ms",
+            Debug2Format(&error),
+            self.backoff.as_millis()
+        );
+        self.ready = false;
+        self.retry_at = Instant::now() + self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_RETRY_BACKOFF);
+    }
 
-const CAT_TAP_2: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x60, 0x60, 0x00, 0x80, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0xa0, 0x80, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x90, 0x90, 0x90, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x71, 0x12, 0x92, 0x91,
-    0x92, 0x90, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x03, 0x03, 0x23, 0x33, 0x38, 0x39, 0x01, 0x02, 0x02, 0x39, 0x31, 0x20, 0x03, 0x03, 0x03,
-    0x03, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+    pub async fn poll(&mut self) {
+        // Sampled while the display is off too, so the graph has no gaps
+        self.redraw |= self.state.wpm_history.sample(self.state.wpm);
 
-const CAT_TAP_EG: [[u8; 160]; 2] = {
-    // Erstes Bild konvertieren
-    const IMG0: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_TAP_1, &mut out);
-        out
-    };
-    // Zweites Bild konvertieren (ersetze CAT_IDLE_2 durch dein zweites QMK‑Array)
-    const IMG1: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_TAP_2, &mut out);
-        out
-    };
-    [IMG0, IMG1]
-};
-
-const CAT_SHOUT_1: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x60, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x40, 0x60, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x90, 0x90, 0x90, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x71, 0x12, 0x96, 0x96,
-    0x96, 0x12, 0x71, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x90, 0x90, 0x90, 0x10, 0x1f,
-    0x00, 0x03, 0x03, 0x03, 0x03, 0x20, 0x31, 0x39, 0x02, 0x02, 0x39, 0x39, 0x30, 0x23, 0x03, 0x03,
-    0x03, 0x23, 0x30, 0x39, 0x39, 0x02, 0x02, 0x39, 0x31, 0x20, 0x03, 0x03, 0x03, 0x03, 0x00, 0x00,
-];
+        if !self.ready {
+            if Instant::now() < self.retry_at {
+                return;
+            }
+            if let Err(error) = self.display.init().await {
+                self.failed(error);
+                return;
+            }
+            info!("Display initialized");
+            self.ready = true;
+            self.backoff = MIN_RETRY_BACKOFF;
+            // The init sets its own brightness, and the display RAM is cleared
+            self.settings_changed = true;
+            self.redraw = true;
+        }
 
-const CAT_SHOUT_2: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xa0, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0xa0, 0x80, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x12, 0x16,
-    0x12, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+        if let Err(error) = self.update().await {
+            self.failed(error);
+        }
+    }
 
-const CAT_SHOUT_EG: [[u8; 160]; 2] = {
-    // Erstes Bild konvertieren
-    const IMG0: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_SHOUT_1, &mut out);
-        out
-    };
-    // Zweites Bild konvertieren (ersetze CAT_IDLE_2 durch dein zweites QMK‑Array)
-    const IMG1: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_SHOUT_2, &mut out);
-        out
-    };
-    [IMG0, IMG1]
-};
-
-const CAT_WAIT_1: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x1e, 0x7e,
-    0x7e, 0x1e, 0x0c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x22, 0x27,
-    0x27, 0x22, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0xc0, 0x60, 0x10, 0x10, 0x60, 0xc0, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xb0, 0x30, 0x00, 0x00, 0x00, 0x00, 0x80,
-    0x00, 0x00, 0x00, 0x00, 0x30, 0xb0, 0x80, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x11, 0x10,
-    0x11, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+    /// Applies the power and draws the current page if it changed
+    async fn update(&mut self) -> Result<(), DisplayError> {
+        let power = if self.settings.display_on {
+            Power::after(self.last_activity.elapsed())
+        } else {
+            Power::Off
+        };
+        if power != self.power || self.settings_changed {
+            self.set_power(power).await?;
+            self.settings_changed = false;
+        }
+        if power == Power::Off {
+            return Ok(());
+        }
 
-const CAT_WAIT_2: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x33, 0xe1, 0x81,
-    0x81, 0xe1, 0x33, 0x1e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x27, 0x2d, 0x28,
-    0x28, 0x2d, 0x27, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xa0, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0xa0, 0x80, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x12, 0x11,
-    0x12, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+        // Counted from when it is shown, a notification waits while the display is off
+        let notification = self.notifications.current();
+        if notification != self.state.notification {
+            self.state.notification = notification;
+            self.redraw = true;
+        }
 
-const CAT_WAIT_EG: [[u8; 160]; 2] = {
-    // Erstes Bild konvertieren
-    const IMG0: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_WAIT_1, &mut out);
-        out
-    };
-    // Zweites Bild konvertieren (ersetze CAT_IDLE_2 durch dein zweites QMK‑Array)
-    const IMG1: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_WAIT_2, &mut out);
-        out
-    };
-    [IMG0, IMG1]
-};
-
-const CAT_WHISP_1: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xa0, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0x20, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x12, 0x11,
-    0x12, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+        if SHIFT_INTERVAL.is_some_and(|interval| self.shift_since.elapsed() >= interval) {
+            self.shift = (self.shift + 1) % PIXEL_SHIFTS.len();
+            self.shift_since = Instant::now();
+            self.redraw = true;
+        }
 
-const CAT_WHISP_2: [u8; 160] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x80, 0x40, 0x40, 0x20, 0x20, 0x20, 0x20,
-    0x20, 0x20, 0x20, 0x20, 0x40, 0x40, 0x80, 0x00, 0x80, 0xc0, 0x20, 0x20, 0xc0, 0x80, 0x00, 0x00,
-    0x80, 0x70, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x01, 0x81, 0xa0, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0x20, 0x20, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x78, 0xc0,
-    0x1f, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x07, 0x01, 0x0c, 0x0c, 0x01, 0x07, 0x1c, 0x10, 0x12, 0x11,
-    0x12, 0x10, 0x70, 0xc0, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f,
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x01, 0x01, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+        if self.state.carousel && self.page_since.elapsed() >= SettingsPage::CAROUSEL_INTERVAL {
+            self.show_page(self.page.next());
+        }
 
-const CAT_WHISP_EG: [[u8; 160]; 2] = {
-    // Erstes Bild konvertieren
-    const IMG0: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_WHISP_1, &mut out);
-        out
-    };
-    // Zweites Bild konvertieren (ersetze CAT_IDLE_2 durch dein zweites QMK‑Array)
-    const IMG1: [u8; 160] = {
-        let mut out = [0u8; 160];
-        qmkpages_to_embedded_graphics_lines::<32, 40>(&CAT_WHISP_2, &mut out);
-        out
-    };
-    [IMG0, IMG1]
-};
-
-const fn qmkpages_to_embedded_graphics_lines<const W: usize, const H: usize>(
-    input: &[u8],
-    output: &mut [u8],
-) {
-    const fn const_get_bit(b: u8, n: usize) -> u8 {
-        ((b >> n) & 1) as u8
-    }
-    if W % 8 != 0 {
-        panic!("width must be multiple of 8");
-    }
-    if output.len() != (W * H + 7) / 8 {
-        panic!("output length must be width*(height/8)");
-    }
-    let bytes_per_row = W / 8;
-    let mut row = 0;
-    while row < H {
-        let mut byte_in_row = 0;
-        while byte_in_row < bytes_per_row {
-            let mut bit_in_byte = 0;
-            while bit_in_byte < 8 {
-                // QMK Page-Mode: vertikale Spalten; row/8 wählt die Page
-                let src_byte = input[bit_in_byte + byte_in_row * 8 + (row / 8) * W];
-                let bit = const_get_bit(src_byte, row % 8);
-                // Zeilenmajor-Index im Ausgabepuffer
-                output[byte_in_row + row * bytes_per_row] |= bit << (7 - bit_in_byte);
-                bit_in_byte += 1;
-            }
-            byte_in_row += 1;
+        // Events mark the state changed, the pages know when an animation frame or the clock
+        // moves on
+        if !self.redraw && !self.pages.due(self.page, &self.state) {
+            return Ok(());
         }
-        row += 1;
+        self.redraw = false;
+
+        self.display.clear_buffer();
+        let mut target = self.display.translated(PIXEL_SHIFTS[self.shift]);
+        // Drawing into the buffer cannot fail
+        let Ok(()) = self.pages.draw(self.page, &self.state, &mut target);
+        self.display.flush().await
     }
 }
@@ -0,0 +1,64 @@
+use embassy_time::Duration;
+use rmk_macro::event;
+
+/// Layers whose share of the key presses is sent to the display
+pub const SUMMARY_LAYERS: usize = 4;
+
+/// Typing statistics of the central, as shown on the display
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct TypingSummary {
+    /// Key presses since the statistics were started, kept in flash
+    pub total_presses: u32,
+    /// Key presses since boot
+    pub session_presses: u32,
+    /// Time spent typing since boot, pauses are left out
+    pub session_typing_secs: u32,
+    /// Share of all key presses made on each layer, in percent
+    pub layer_percent: [u8; SUMMARY_LAYERS],
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct TypingSummaryEvent(pub TypingSummary);
+
+/// Counters of the typing statistics, kept by `TypingStats` on the central and read by the HID
+/// queries for the heatmap
+pub struct TypingCounters<const ROW: usize, const COL: usize, const NUM_LAYER: usize> {
+    /// Key presses since the statistics were started
+    pub total_presses: u32,
+    /// Typing time before this session, from flash
+    pub typing_secs_before: u32,
+    /// Time spent typing since boot, pauses are left out
+    pub session_typing: Duration,
+    pub layer_presses: [u32; NUM_LAYER],
+    /// Key presses per matrix position, row by row
+    pub key_presses: [[u32; COL]; ROW],
+}
+
+impl<const ROW: usize, const COL: usize, const NUM_LAYER: usize> Default
+    for TypingCounters<ROW, COL, NUM_LAYER>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const ROW: usize, const COL: usize, const NUM_LAYER: usize>
+    TypingCounters<ROW, COL, NUM_LAYER>
+{
+    pub const fn new() -> Self {
+        Self {
+            total_presses: 0,
+            typing_secs_before: 0,
+            session_typing: Duration::from_ticks(0),
+            layer_presses: [0; NUM_LAYER],
+            key_presses: [[0; COL]; ROW],
+        }
+    }
+
+    /// Time spent typing since the statistics were started
+    pub fn typing_secs(&self) -> u32 {
+        self.typing_secs_before
+            .saturating_add(self.session_typing.as_secs() as u32)
+    }
+}
@@ -0,0 +1,246 @@
+//! Typing statistics of the central: key presses per key and per layer, and the time spent
+//! typing.
+//!
+//! The counters are kept in flash records of their own and a summary is sent to the display.
+//! The host reads all counters, the heatmap among them, with the queries of
+//! [`crate::hidquery`].
+use crate::flashstore::{FlashStore, MAX_KINDS, RECORD_DATA_LEN};
+use crate::typingstate::{TypingCounters, TypingSummary, TypingSummaryEvent, SUMMARY_LAYERS};
+use core::cell::RefCell;
+use defmt::{info, warn};
+use embassy_time::{Duration, Instant};
+use embedded_storage_async::nor_flash::NorFlash;
+use rmk::event::publish_event;
+use rmk::event::{KeyPos, KeyboardEvent, KeyboardEventPos, LayerChangeEvent};
+use rmk_macro::processor;
+
+/// `u32` counters in a flash record
+const COUNTERS_PER_RECORD: usize = RECORD_DATA_LEN / 4;
+/// Longest pause between key presses still counted as typing
+const TYPING_GAP: Duration = Duration::from_secs(5);
+/// Time from the first unsaved key press until the counters are saved. Typing for a while
+/// costs one write per changed record instead of one per key press, the key presses of the
+/// last interval before a power loss are lost.
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+/// The summary is sent to the display at most this often while typing, and again from time
+/// to time for a peripheral booting later
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(5);
+const REPUBLISH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Counts the key presses. The counters are numbered for the flash records: the total key
+/// presses, the typing time, the presses per layer and the presses per key, row by row.
+#[processor(subscribe = [LayerChangeEvent, KeyboardEvent], poll_interval = 1000)]
+pub struct TypingStats<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, F: NorFlash>
+{
+    counters: &'a RefCell<TypingCounters<ROW, COL, NUM_LAYER>>,
+    current_layer: u8,
+    store: FlashStore<F>,
+    loaded: bool,
+    /// Records changed since they were saved, bit `n` is record kind `n`
+    dirty: u64,
+    /// First key press not saved yet
+    unsaved_since: Option<Instant>,
+    session_presses: u32,
+    last_press: Option<Instant>,
+    summary_changed: bool,
+    published: Instant,
+}
+
+impl<'a, const ROW: usize, const COL: usize, const NUM_LAYER: usize, F: NorFlash>
+    TypingStats<'a, ROW, COL, NUM_LAYER, F>
+{
+    const TOTAL_PRESSES: usize = 0;
+    const TYPING_SECS: usize = 1;
+    const FIRST_LAYER: usize = 2;
+    const FIRST_KEY: usize = Self::FIRST_LAYER + NUM_LAYER;
+    const COUNTERS: usize = Self::FIRST_KEY + ROW * COL;
+    const RECORDS: usize = Self::COUNTERS.div_ceil(COUNTERS_PER_RECORD);
+
+    pub fn new(
+        counters: &'a RefCell<TypingCounters<ROW, COL, NUM_LAYER>>,
+        store: FlashStore<F>,
+    ) -> Self {
+        assert!(Self::RECORDS <= MAX_KINDS as usize && Self::RECORDS <= u64::BITS as usize);
+        Self {
+            counters,
+            current_layer: 0,
+            store,
+            loaded: false,
+            dirty: 0,
+            unsaved_since: None,
+            session_presses: 0,
+            last_press: None,
+            summary_changed: true,
+            published: Instant::now(),
+        }
+    }
+
+    fn counter(&self, i: usize) -> u32 {
+        let counters = self.counters.borrow();
+        match i {
+            Self::TOTAL_PRESSES => counters.total_presses,
+            Self::TYPING_SECS => counters.typing_secs(),
+            i if i < Self::FIRST_KEY => counters.layer_presses[i - Self::FIRST_LAYER],
+            i => {
+                let key = i - Self::FIRST_KEY;
+                counters.key_presses[key / COL][key % COL]
+            }
+        }
+    }
+
+    /// Adds `value` to counter `i`
+    fn add(&mut self, i: usize, value: u32) {
+        {
+            let mut counters = self.counters.borrow_mut();
+            let counters = &mut *counters;
+            let counter = match i {
+                Self::TOTAL_PRESSES => &mut counters.total_presses,
+                Self::TYPING_SECS => &mut counters.typing_secs_before,
+                i if i < Self::FIRST_KEY => &mut counters.layer_presses[i - Self::FIRST_LAYER],
+                i => {
+                    let key = i - Self::FIRST_KEY;
+                    &mut counters.key_presses[key / COL][key % COL]
+                }
+            };
+            *counter = counter.saturating_add(value);
+        }
+        self.mark_dirty(i);
+    }
+
+    fn mark_dirty(&mut self, i: usize) {
+        self.dirty |= 1 << (i / COUNTERS_PER_RECORD);
+        self.unsaved_since.get_or_insert_with(Instant::now);
+    }
+
+    async fn on_layer_change_event(&mut self, event: LayerChangeEvent) {
+        self.current_layer = event.layer;
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        if !event.pressed {
+            return;
+        }
+        let KeyboardEventPos::Key(KeyPos { row, col }) = event.pos else {
+            return;
+        };
+        let (row, col) = (row as usize, col as usize);
+        if row >= ROW || col >= COL {
+            return;
+        }
+
+        self.add(Self::TOTAL_PRESSES, 1);
+        if (self.current_layer as usize) < NUM_LAYER {
+            self.add(Self::FIRST_LAYER + self.current_layer as usize, 1);
+        }
+        self.add(Self::FIRST_KEY + row * COL + col, 1);
+        self.session_presses += 1;
+
+        let typing_secs_changed = {
+            let mut counters = self.counters.borrow_mut();
+            let typing_secs = counters.session_typing.as_secs();
+            if let Some(gap) = self.last_press.map(|last| last.elapsed()) {
+                if gap <= TYPING_GAP {
+                    counters.session_typing += gap;
+                }
+            }
+            counters.session_typing.as_secs() != typing_secs
+        };
+        if typing_secs_changed {
+            self.mark_dirty(Self::TYPING_SECS);
+        }
+        self.last_press = Some(Instant::now());
+        self.summary_changed = true;
+    }
+
+    fn summary(&self) -> TypingSummary {
+        let counters = self.counters.borrow();
+        let layer_total: u32 = counters.layer_presses.iter().sum();
+        let mut layer_percent = [0; SUMMARY_LAYERS];
+        for (percent, presses) in layer_percent.iter_mut().zip(counters.layer_presses) {
+            *percent = (presses as u64 * 100)
+                .checked_div(layer_total as u64)
+                .unwrap_or(0) as u8;
+        }
+        TypingSummary {
+            total_presses: counters.total_presses,
+            session_presses: self.session_presses,
+            session_typing_secs: counters.session_typing.as_secs() as u32,
+            layer_percent,
+        }
+    }
+
+    async fn load(&mut self) {
+        for record in 0..Self::RECORDS {
+            let data = match self.store.load(record as u8).await {
+                Ok(Some(data)) => data,
+                Ok(None) => continue,
+                Err(_) => {
+                    warn!("Failed to load typing stats");
+                    return;
+                }
+            };
+            let first = record * COUNTERS_PER_RECORD;
+            let last = (first + COUNTERS_PER_RECORD).min(Self::COUNTERS);
+            for (i, value) in (first..last).zip(data.chunks_exact(4)) {
+                // Key presses before the load are kept
+                self.add(i, u32::from_le_bytes(value.try_into().unwrap()));
+            }
+        }
+        info!(
+            "Loaded typing stats, {} key presses so far",
+            self.counter(Self::TOTAL_PRESSES)
+        );
+        // Nothing to save unless there were key presses before the load
+        if self.session_presses == 0 {
+            self.dirty = 0;
+            self.unsaved_since = None;
+        }
+    }
+
+    async fn save(&mut self) {
+        for record in 0..Self::RECORDS {
+            if self.dirty & (1 << record) == 0 {
+                continue;
+            }
+            let mut data = [0u8; RECORD_DATA_LEN];
+            let first = record * COUNTERS_PER_RECORD;
+            let last = (first + COUNTERS_PER_RECORD).min(Self::COUNTERS);
+            for (i, value) in (first..last).zip(data.chunks_exact_mut(4)) {
+                value.copy_from_slice(&self.counter(i).to_le_bytes());
+            }
+            if self.store.save(record as u8, &data).await.is_err() {
+                warn!("Failed to save typing stats");
+                // Try again later
+                self.unsaved_since = Some(Instant::now());
+                return;
+            }
+            self.dirty &= !(1 << record);
+        }
+    }
+
+    pub async fn poll(&mut self) {
+        if !self.loaded {
+            self.load().await;
+            self.loaded = true;
+        }
+
+        if self
+            .unsaved_since
+            .is_some_and(|since| since.elapsed() >= SAVE_INTERVAL)
+        {
+            self.unsaved_since = None;
+            self.save().await;
+        }
+
+        let interval = if self.summary_changed {
+            PUBLISH_INTERVAL
+        } else {
+            REPUBLISH_INTERVAL
+        };
+        if self.published.elapsed() >= interval {
+            publish_event(TypingSummaryEvent(self.summary()));
+            self.summary_changed = false;
+            self.published = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Reads the USB state of the central from the RP2040's USB controller.
+//!
+//! rmk owns the USB driver and doesn't publish the bus state, so it is read from the SIE status
+//! and the address the host assigned. Reading them doesn't disturb the driver.
+//!
+//! The keyboard isn't told when the host locks, the closest it sees is the host suspending the
+//! bus when it goes to sleep. That posts a notification.
+use crate::notification::{Notification, NotificationEvent, NOTIFICATION_TTL};
+use crate::usbstate::{UsbState, UsbStateEvent};
+use crate::viallock::VIAL_UNLOCKED;
+use core::sync::atomic::Ordering;
+use defmt::info;
+use embassy_rp::pac::USBCTRL_REGS;
+use embassy_time::{Duration, Instant, Timer};
+use rmk::event::publish_event;
+
+/// Interval of reading the state
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// The state is sent again from time to time for a peripheral booting later
+const REPUBLISH_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct UsbMonitor {
+    state: Option<UsbState>,
+    published: Instant,
+}
+
+impl Default for UsbMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsbMonitor {
+    pub fn new() -> Self {
+        Self {
+            state: None,
+            published: Instant::now(),
+        }
+    }
+
+    fn read_state() -> UsbState {
+        let status = USBCTRL_REGS.sie_status().read();
+        if !status.vbus_detected() {
+            UsbState::Detached
+        } else if status.suspended() {
+            UsbState::Suspended
+        } else if USBCTRL_REGS.addr_endp().read().address() != 0 {
+            // The host assigns the address during enumeration, right before configuring
+            if VIAL_UNLOCKED.load(Ordering::Relaxed) {
+                UsbState::VialUnlocked
+            } else {
+                UsbState::Configured
+            }
+        } else {
+            UsbState::Powered
+        }
+    }
+
+    pub async fn run(&mut self) {
+        loop {
+            let state = Self::read_state();
+            if self.state != Some(state) {
+                info!("USB {}", state);
+                let configured = matches!(
+                    self.state,
+                    Some(UsbState::Configured | UsbState::VialUnlocked)
+                );
+                if configured && state == UsbState::Suspended {
+                    publish_event(NotificationEvent(Notification::new(
+                        "HOST SUSPENDED",
+                        NOTIFICATION_TTL,
+                    )));
+                }
+                self.state = Some(state);
+                self.published = Instant::now();
+                publish_event(UsbStateEvent(state));
+            } else if self.published.elapsed() >= REPUBLISH_INTERVAL {
+                self.published = Instant::now();
+                publish_event(UsbStateEvent(state));
+            }
+            Timer::after(POLL_INTERVAL).await;
+        }
+    }
+}
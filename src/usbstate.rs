@@ -0,0 +1,33 @@
+use rmk_macro::event;
+
+/// USB connection of the central to the host
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub enum UsbState {
+    /// No VBUS, running from the other half or a battery
+    #[default]
+    Detached,
+    /// Powered, but the host hasn't configured the keyboard yet
+    Powered,
+    /// Enumerated, keys reach the host
+    Configured,
+    /// The host suspended the bus, usually because it sleeps
+    Suspended,
+    /// Enumerated with Vial unlocked, the keymap can be changed from the host
+    VialUnlocked,
+}
+
+impl UsbState {
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => UsbState::Powered,
+            2 => UsbState::Configured,
+            3 => UsbState::Suspended,
+            4 => UsbState::VialUnlocked,
+            _ => UsbState::Detached,
+        }
+    }
+}
+
+#[event(channel_size = 2)]
+#[derive(Clone, Copy, Debug)]
+pub struct UsbStateEvent(pub UsbState);
@@ -0,0 +1,67 @@
+//! Tracks whether Vial was unlocked on the central.
+//!
+//! rmk unlocks Vial when the unlock keys are held together while Vial asks for it, but it
+//! doesn't publish its lock state. [`VialLockTracker`] watches the unlock keys instead: held
+//! together for [`UNLOCK_HOLD`] counts as unlocked until the next restart, since locking again
+//! is a host command the firmware doesn't see. The USB monitor shows it as
+//! [`UsbState::VialUnlocked`](crate::usbstate::UsbState::VialUnlocked).
+use core::sync::atomic::{AtomicBool, Ordering};
+use defmt::info;
+use embassy_time::{Duration, Instant};
+use rmk::event::{KeyPos, KeyboardEvent, KeyboardEventPos};
+use rmk_macro::processor;
+
+/// Keys held to unlock Vial, (row, col): ~ and ESC
+pub const VIAL_UNLOCK_KEYS: [(u8, u8); 2] = [(0, 0), (2, 0)];
+/// Time the unlock keys are held until Vial counts as unlocked
+const UNLOCK_HOLD: Duration = Duration::from_secs(2);
+
+/// Vial was unlocked since the last restart
+pub static VIAL_UNLOCKED: AtomicBool = AtomicBool::new(false);
+
+#[processor(subscribe = [KeyboardEvent], poll_interval = 100)]
+pub struct VialLockTracker {
+    held: [bool; VIAL_UNLOCK_KEYS.len()],
+    /// All unlock keys are held since then
+    held_since: Option<Instant>,
+}
+
+impl Default for VialLockTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VialLockTracker {
+    pub fn new() -> Self {
+        Self {
+            held: [false; VIAL_UNLOCK_KEYS.len()],
+            held_since: None,
+        }
+    }
+
+    async fn on_keyboard_event(&mut self, event: KeyboardEvent) {
+        let KeyboardEventPos::Key(KeyPos { row, col }) = event.pos else {
+            return;
+        };
+        let Some(i) = VIAL_UNLOCK_KEYS.iter().position(|&key| key == (row, col)) else {
+            return;
+        };
+        self.held[i] = event.pressed;
+        self.held_since = if self.held.iter().all(|&held| held) {
+            self.held_since.or(Some(Instant::now()))
+        } else {
+            None
+        };
+    }
+
+    pub async fn poll(&mut self) {
+        if self
+            .held_since
+            .is_some_and(|since| since.elapsed() >= UNLOCK_HOLD)
+            && !VIAL_UNLOCKED.swap(true, Ordering::Relaxed)
+        {
+            info!("Vial unlock keys held, counting Vial as unlocked");
+        }
+    }
+}
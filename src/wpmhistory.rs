@@ -0,0 +1,80 @@
+//! WPM over time, for the graph on the stats page
+use embassy_time::{Duration, Instant};
+
+/// Samples kept, one per pixel column of the rotated display
+pub const WPM_SAMPLES: usize = 32;
+/// Time between samples, the graph spans a bit more than a minute
+pub const WPM_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct WpmHistory {
+    /// Ring buffer of the last samples, `next` is the oldest once it is full
+    samples: [u16; WPM_SAMPLES],
+    next: usize,
+    len: usize,
+    /// Sum and count of the samples taken while typing, for the session average
+    typing_sum: u32,
+    typing_samples: u32,
+    sampled: Instant,
+}
+
+impl Default for WpmHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WpmHistory {
+    pub fn new() -> Self {
+        Self {
+            samples: [0; WPM_SAMPLES],
+            next: 0,
+            len: 0,
+            typing_sum: 0,
+            typing_samples: 0,
+            sampled: Instant::now(),
+        }
+    }
+
+    /// Takes a sample of the current WPM if `WPM_SAMPLE_INTERVAL` has passed, returns whether it
+    /// did
+    pub fn sample(&mut self, wpm: u16) -> bool {
+        if self.sampled.elapsed() < WPM_SAMPLE_INTERVAL {
+            return false;
+        }
+        self.sampled = Instant::now();
+        self.push(wpm);
+        true
+    }
+
+    pub fn push(&mut self, wpm: u16) {
+        self.samples[self.next] = wpm;
+        self.next = (self.next + 1) % WPM_SAMPLES;
+        self.len = (self.len + 1).min(WPM_SAMPLES);
+        // Pauses would drag the average down to nothing
+        if wpm > 0 {
+            self.typing_sum += wpm as u32;
+            self.typing_samples += 1;
+        }
+    }
+
+    /// Samples in the order they were taken, the latest last
+    pub fn iter(&self) -> impl Iterator<Item = u16> + '_ {
+        let start = (self.next + WPM_SAMPLES - self.len) % WPM_SAMPLES;
+        (0..self.len).map(move |i| self.samples[(start + i) % WPM_SAMPLES])
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Average WPM of the session while typing
+    pub fn average(&self) -> u16 {
+        self.typing_sum
+            .checked_div(self.typing_samples)
+            .unwrap_or(0) as u16
+    }
+}